@@ -0,0 +1,68 @@
+//! Rough timings of common operations on large strings
+//!
+//! Run with `cargo bench`.
+
+use perroquet::{RichString, BOLD, RED, YELLOW};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+fn bench<F: FnMut()>(name: &str, iterations: u32, mut f: F) {
+    let start = Instant::now();
+    for _ in 0..iterations {
+        f();
+    }
+    let elapsed: Duration = start.elapsed();
+    println!(
+        "{:<32} {:>12.3?} / iter ({} iterations)",
+        name,
+        elapsed / iterations,
+        iterations
+    );
+}
+
+fn log_view(lines: usize) -> RichString {
+    let mut string = RichString::new();
+    for i in 0..lines {
+        string.push(&RichString::from("[ERROR] ", RED & BOLD));
+        string.push_plain(&format!("line {} — ünïcödé payload\n", i));
+    }
+    string
+}
+
+fn main() {
+    let large = log_view(50_000);
+    let len = large.len();
+
+    bench("push 50k lines", 10, || {
+        black_box(log_view(50_000));
+    });
+
+    bench("style_at (large)", 100_000, || {
+        black_box(large.style_at(black_box(len / 2)));
+    });
+
+    bench("substring (large)", 10_000, || {
+        black_box(large.substring(black_box(len / 2), black_box(len / 2 + 80)));
+    });
+
+    bench("styled_chars (large)", 10, || {
+        black_box(large.styled_chars().count());
+    });
+
+    let mut edited = large.clone();
+    bench("insert in the middle (large)", 1_000, || {
+        edited.insert(black_box(len / 2), &RichString::from("inserted", YELLOW));
+    });
+
+    let byte = edited.raw().len() / 3;
+    let byte = (byte..)
+        .find(|b| edited.raw().is_char_boundary(*b))
+        .unwrap();
+    bench("insert_at_byte (large)", 1_000, || {
+        black_box(edited.insert_at_byte(black_box(byte), &RichString::from("x", BOLD))).unwrap();
+    });
+
+    bench("split lines (large)", 10, || {
+        black_box(large.split("\n"));
+    });
+}
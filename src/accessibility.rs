@@ -0,0 +1,277 @@
+use crate::rgb::{from_linear, to_linear};
+use crate::{Color, Decoration, Feature, RichString, Style, Theme};
+
+/// Types of color blindness that can be simulated
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ColorBlindness {
+    /// No red cones, red and green are confused
+    PROTANOPIA,
+    /// No green cones, red and green are confused
+    DEUTERANOPIA,
+    /// No blue cones, blue and yellow are confused
+    TRITANOPIA,
+}
+
+impl ColorBlindness {
+    /// Returns the simulation matrix, applied to linear RGB
+    ///
+    /// Values are from Machado, Oliveira and Fernandes (2009), with a
+    /// severity of 1.
+    fn matrix(self) -> [[f64; 3]; 3] {
+        match self {
+            Self::PROTANOPIA => [
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998],
+            ],
+            Self::DEUTERANOPIA => [
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881],
+            ],
+            Self::TRITANOPIA => [
+                [1.255528, -0.076749, -0.178779],
+                [-0.078411, 0.930809, 0.147602],
+                [0.004733, 0.691367, 0.303900],
+            ],
+        }
+    }
+
+    /// Returns the color as seen with this color blindness
+    ///
+    /// [Color::INHERIT] is returned unchanged.
+    pub fn simulate_color(self, color: Color) -> Color {
+        match color.to_rgb() {
+            Some((r, g, b)) => {
+                let input = [to_linear(r), to_linear(g), to_linear(b)];
+                let [r, g, b] = self.matrix().map(|row| {
+                    from_linear(row[0] * input[0] + row[1] * input[1] + row[2] * input[2])
+                });
+                Color::RGB(r, g, b)
+            }
+            None => color,
+        }
+    }
+
+    /// Returns the style with its colors as seen with this color blindness
+    pub fn simulate_style(self, style: Style) -> Style {
+        Style {
+            foreground: self.simulate_color(style.foreground),
+            background: self.simulate_color(style.background),
+            ..style
+        }
+    }
+}
+
+impl Style {
+    /// Replaces the colors of the style with attributes
+    ///
+    /// The text color becomes an attribute depending on its hue, so that
+    /// the meaning doesn't rely on colors alone:
+    ///
+    /// | hue            | attributes        |
+    /// |----------------|-------------------|
+    /// | red            | bold and reverse  |
+    /// | yellow, orange | bold and underline|
+    /// | green          | bold              |
+    /// | cyan to purple | underline         |
+    /// | black to white | none              |
+    ///
+    /// A colored background becomes reverse. Attributes already set by the
+    /// style are kept.
+    pub fn to_high_contrast(self) -> Self {
+        let mut substitute = match hue(self.foreground) {
+            Some(hue) if !(20.0..330.0).contains(&hue) => Self {
+                bold: Feature::ENABLED,
+                reverse: Feature::ENABLED,
+                ..Self::plain()
+            },
+            Some(hue) if hue < 75.0 => Self {
+                bold: Feature::ENABLED,
+                decoration: Decoration::UNDERLINE,
+                ..Self::plain()
+            },
+            Some(hue) if hue < 165.0 => Self {
+                bold: Feature::ENABLED,
+                ..Self::plain()
+            },
+            Some(_) => Self {
+                decoration: Decoration::UNDERLINE,
+                ..Self::plain()
+            },
+            None => Self::plain(),
+        };
+        if hue(self.background).is_some() {
+            substitute.reverse = Feature::ENABLED;
+        }
+
+        Self {
+            foreground: Color::INHERIT,
+            background: Color::INHERIT,
+            ..self
+        } & substitute
+    }
+}
+
+/// Returns the hue of a color in degrees, or `None` if it is a shade of grey
+fn hue(color: Color) -> Option<f64> {
+    let (r, g, b) = color.to_rgb()?;
+    let (r, g, b) = (r as f64, g as f64, b as f64);
+    let max = r.max(g).max(b);
+    let chroma = max - r.min(g).min(b);
+
+    if chroma < 48.0 {
+        return None;
+    }
+
+    let sector = if max == r {
+        ((g - b) / chroma).rem_euclid(6.0)
+    } else if max == g {
+        (b - r) / chroma + 2.0
+    } else {
+        (r - g) / chroma + 4.0
+    };
+    Some(sector * 60.0)
+}
+
+impl RichString {
+    /// Changes the colors of the string as seen with a color blindness
+    ///
+    /// Styles from a theme are not affected until they are resolved, use
+    /// [Theme::simulate()] to change them.
+    pub fn simulate(&mut self, kind: ColorBlindness) {
+        self.restyle(|style| kind.simulate_style(style));
+    }
+
+    /// See [Self::simulate()].
+    pub fn into_simulated(mut self, kind: ColorBlindness) -> Self {
+        self.simulate(kind);
+        self
+    }
+
+    /// Replaces the colors of the string with attributes
+    ///
+    /// See [Style::to_high_contrast()].
+    pub fn high_contrast(&mut self) {
+        self.restyle(Style::to_high_contrast);
+    }
+
+    /// See [Self::high_contrast()].
+    pub fn into_high_contrast(mut self) -> Self {
+        self.high_contrast();
+        self
+    }
+}
+
+impl Theme {
+    /// Changes the colors of the theme as seen with a color blindness
+    pub fn simulate(&mut self, kind: ColorBlindness) {
+        self.restyle(|style| kind.simulate_style(style));
+    }
+
+    /// Replaces the colors of the theme with attributes
+    ///
+    /// See [Style::to_high_contrast()].
+    pub fn high_contrast(&mut self) {
+        self.restyle(Style::to_high_contrast);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shortcuts::*;
+
+    #[test]
+    fn test_simulate_color() {
+        for kind in [
+            ColorBlindness::PROTANOPIA,
+            ColorBlindness::DEUTERANOPIA,
+            ColorBlindness::TRITANOPIA,
+        ] {
+            assert_eq!(kind.simulate_color(Color::INHERIT), Color::INHERIT);
+            assert_eq!(kind.simulate_color(Color::BLACK), Color::RGB(0, 0, 0));
+            assert_eq!(kind.simulate_color(Color::WHITE), Color::RGB(255, 255, 255));
+        }
+
+        // red and green look alike without red or green cones
+        for kind in [ColorBlindness::PROTANOPIA, ColorBlindness::DEUTERANOPIA] {
+            let red = kind.simulate_color(Color::RGB(200, 60, 0));
+            let green = kind.simulate_color(Color::RGB(90, 130, 0));
+            assert!((hue(red).unwrap() - hue(green).unwrap()).abs() < 10.0);
+        }
+        let red = ColorBlindness::TRITANOPIA.simulate_color(Color::RGB(200, 60, 0));
+        let green = ColorBlindness::TRITANOPIA.simulate_color(Color::RGB(90, 130, 0));
+        assert_ne!(red, green);
+    }
+
+    #[test]
+    fn test_simulate() {
+        let string = (RichString::from("fail", RED & BOLD) + " ok")
+            .into_simulated(ColorBlindness::DEUTERANOPIA);
+
+        assert!(matches!(string.style_at(0).foreground, Color::RGB(..)));
+        assert_eq!(string.style_at(0).bold, Feature::ENABLED);
+        assert_eq!(string.style_at(4), Style::plain());
+
+        let mut theme = Theme::new().with("error", ON_RED);
+        theme.simulate(ColorBlindness::PROTANOPIA);
+        assert!(matches!(theme.get("error").background, Color::RGB(..)));
+    }
+
+    #[test]
+    fn test_to_high_contrast() {
+        let bold_reverse = Style {
+            bold: Feature::ENABLED,
+            reverse: Feature::ENABLED,
+            ..Style::plain()
+        };
+
+        assert_eq!(RED.to_high_contrast(), bold_reverse);
+        assert_eq!(
+            Style {
+                foreground: Color::FIXED(196),
+                ..Style::plain()
+            }
+            .to_high_contrast(),
+            bold_reverse
+        );
+        assert_eq!(YELLOW.to_high_contrast(), BOLD & UNDERLINE);
+        assert_eq!(GREEN.to_high_contrast(), BOLD);
+        assert_eq!(BLUE.to_high_contrast(), UNDERLINE);
+        assert_eq!(PURPLE.to_high_contrast(), UNDERLINE);
+        assert_eq!(LIGHTGREY.to_high_contrast(), Style::plain());
+        assert_eq!((ON_BLACK & ITALIC).to_high_contrast(), ITALIC);
+        assert_eq!(
+            ON_GREEN.to_high_contrast(),
+            Style {
+                reverse: Feature::ENABLED,
+                ..Style::plain()
+            }
+        );
+        assert_eq!(
+            Style {
+                bold: Feature::DISABLED,
+                ..GREEN
+            }
+            .to_high_contrast(),
+            Style {
+                bold: Feature::DISABLED,
+                ..Style::plain()
+            }
+        );
+    }
+
+    #[test]
+    fn test_high_contrast() {
+        let string =
+            (RichString::from("fail", RED) + RichString::from("ok", GREEN)).into_high_contrast();
+        assert_eq!(string.style_at(0).reverse, Feature::ENABLED);
+        assert_eq!(string.style_at(4), BOLD);
+
+        let mut theme = Theme::new().with("error", RED).with("error.path", BLUE);
+        theme.high_contrast();
+        assert_eq!(theme.get("error.path").decoration, Decoration::UNDERLINE);
+        assert_eq!(theme.get("error.path").foreground, Color::INHERIT);
+    }
+}
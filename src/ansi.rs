@@ -0,0 +1,179 @@
+use crate::{Color, Decoration, Feature, RichString, RichText, Style};
+
+impl Style {
+    /// Returns the SGR codes for the style, like `01;31`
+    ///
+    /// This is the opposite of [Self::from_sgr()]. Properties that are
+    /// inherited don't produce any code, so the plain style gives an empty
+    /// string.
+    ///
+    /// There is no code to turn off only bold or only dim: code 22 turns off
+    /// both. So if one of them is disabled and the other is inherited, the
+    /// other one is disabled too once the codes are applied, and reading
+    /// them back with [Self::from_sgr()] disables both.
+    pub fn to_sgr(&self) -> String {
+        let mut codes: Vec<String> = Vec::new();
+
+        if self.bold == Feature::DISABLED || self.dim == Feature::DISABLED {
+            codes.push(String::from("22"));
+        }
+        let features = [
+            (self.bold, "1", None),
+            (self.dim, "2", None),
+            (self.italic, "3", Some("23")),
+            (self.blink, "5", Some("25")),
+            (self.reverse, "7", Some("27")),
+        ];
+        for (feature, enabled, disabled) in features {
+            match (feature, disabled) {
+                (Feature::ENABLED, _) => codes.push(String::from(enabled)),
+                (Feature::DISABLED, Some(disabled)) => codes.push(String::from(disabled)),
+                _ => {}
+            }
+        }
+        match self.decoration {
+            Decoration::INHERIT => {}
+            Decoration::NONE => codes.push(String::from("24;29")),
+            Decoration::UNDERLINE => codes.push(String::from("4")),
+            Decoration::LINETHROUGH => codes.push(String::from("9")),
+        }
+        if let Some(code) = color_code(self.foreground, 30) {
+            codes.push(code);
+        }
+        if let Some(code) = color_code(self.background, 40) {
+            codes.push(code);
+        }
+
+        codes.join(";")
+    }
+}
+
+/// Returns the SGR code for a color, `base` being 30 for the text color and
+/// 40 for the background
+fn color_code(color: Color, base: u8) -> Option<String> {
+    match color {
+        Color::INHERIT => None,
+        Color::RGB(r, g, b) => Some(format!("{};2;{};{};{}", base + 8, r, g, b)),
+        Color::FIXED(index) => Some(format!("{};5;{}", base + 8, index)),
+        named => named.index().map(|index| match index {
+            0..=7 => (base + index).to_string(),
+            _ => (base + 60 + index - 8).to_string(),
+        }),
+    }
+}
+
+impl RichString {
+    /// Returns the string with the escape sequences that terminals use to
+    /// display styles
+    ///
+    /// Each styled run is followed by a reset, so the output doesn't leak
+    /// its style to text printed after it.
+    pub fn to_ansi(&self) -> String {
+        let mut output = String::with_capacity(self.raw().len());
+
+        for (text, style) in self.spans() {
+            let codes = style.to_sgr();
+            if codes.is_empty() {
+                output.push_str(text);
+            } else {
+                output.push_str(&format!("\x1b[{}m{}\x1b[0m", codes, text));
+            }
+        }
+
+        output
+    }
+}
+
+impl RichText {
+    /// Returns the lines with the escape sequences that terminals use to
+    /// display styles, separated by newlines
+    ///
+    /// See [RichString::to_ansi()].
+    pub fn to_ansi(&self) -> String {
+        self.lines()
+            .iter()
+            .map(RichString::to_ansi)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shortcuts::*;
+
+    #[test]
+    fn test_to_sgr() {
+        assert_eq!(Style::plain().to_sgr(), "");
+        assert_eq!((BOLD & RED).to_sgr(), "1;31");
+        assert_eq!((ITALIC & ON_BLUE & UNDERLINE).to_sgr(), "3;4;44");
+        assert_eq!(
+            Style {
+                foreground: Color::FIXED(208),
+                background: Color::RGB(1, 2, 3),
+                ..Style::plain()
+            }
+            .to_sgr(),
+            "38;5;208;48;2;1;2;3"
+        );
+        assert_eq!(
+            Style {
+                foreground: Color::LIGHTCYAN,
+                background: Color::DARKGREY,
+                bold: Feature::DISABLED,
+                dim: Feature::ENABLED,
+                reverse: Feature::DISABLED,
+                decoration: Decoration::NONE,
+                ..Style::plain()
+            }
+            .to_sgr(),
+            "22;2;27;24;29;96;100"
+        );
+
+        for style in [BOLD & RED, ITALIC & ON_BLUE & UNDERLINE, PURPLE & ON_BLACK] {
+            assert_eq!(Style::from_sgr(&style.to_sgr()), Ok(style));
+        }
+    }
+
+    #[test]
+    fn test_to_sgr_bold_and_dim() {
+        let style = |bold, dim| Style {
+            bold,
+            dim,
+            ..Style::plain()
+        };
+
+        // 22 can't turn off bold or dim alone
+        let no_bold = style(Feature::DISABLED, Feature::INHERIT);
+        assert_eq!(no_bold.to_sgr(), "22");
+        assert_eq!(
+            Style::from_sgr(&no_bold.to_sgr()),
+            Ok(style(Feature::DISABLED, Feature::DISABLED))
+        );
+        let no_dim = style(Feature::INHERIT, Feature::DISABLED);
+        assert_eq!(
+            Style::from_sgr(&no_dim.to_sgr()),
+            Ok(style(Feature::DISABLED, Feature::DISABLED))
+        );
+
+        // the other one can be turned on again
+        for style in [
+            style(Feature::DISABLED, Feature::ENABLED),
+            style(Feature::ENABLED, Feature::DISABLED),
+            style(Feature::DISABLED, Feature::DISABLED),
+        ] {
+            assert_eq!(Style::from_sgr(&style.to_sgr()), Ok(style));
+        }
+    }
+
+    #[test]
+    fn test_to_ansi() {
+        let string = RichString::from("error", BOLD & RED) + ": not found";
+        assert_eq!(string.to_ansi(), "\x1b[1;31merror\x1b[0m: not found");
+        assert_eq!(RichString::new().to_ansi(), "");
+
+        let text = RichText::from(RichString::from("a\nb", BLUE));
+        assert_eq!(text.to_ansi(), "\x1b[34ma\x1b[0m\n\x1b[34mb\x1b[0m");
+    }
+}
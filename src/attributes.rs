@@ -1,7 +1,16 @@
+use crate::error::ParseStyleError;
+use std::fmt;
+use std::str::FromStr;
+
 /// Colors
-#[derive(Debug, Copy, Clone, PartialEq)]
+///
+/// Colors are ordered as they are declared, palette colors by their index
+/// and RGB colors by their red, then green, then blue component.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Color {
     /// Don't change the color
+    #[default]
     INHERIT,
     /// Black, default background color of the terminal
     BLACK,
@@ -35,12 +44,45 @@ pub enum Color {
     LIGHTPURPLE,
     /// White, *not the default text color of the terminal, use `LIGHTGREY` for that purpose*
     WHITE,
+    /// Color from the 256-color palette of the terminal
+    FIXED(u8),
+    /// True color, from its red, green and blue components
+    RGB(u8, u8, u8),
+}
+
+impl Color {
+    /// Returns the color at an index of the 256-color palette
+    ///
+    /// The first 16 colors of the palette are the named ones.
+    pub fn palette(index: u8) -> Self {
+        match index {
+            0 => Self::BLACK,
+            1 => Self::RED,
+            2 => Self::GREEN,
+            3 => Self::YELLOW,
+            4 => Self::BLUE,
+            5 => Self::PURPLE,
+            6 => Self::CYAN,
+            7 => Self::LIGHTGREY,
+            8 => Self::DARKGREY,
+            9 => Self::LIGHTRED,
+            10 => Self::LIGHTGREEN,
+            11 => Self::LIGHTYELLOW,
+            12 => Self::LIGHTBLUE,
+            13 => Self::LIGHTPURPLE,
+            14 => Self::LIGHTCYAN,
+            15 => Self::WHITE,
+            _ => Self::FIXED(index),
+        }
+    }
 }
 
 /// Underline or line-through
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Decoration {
     /// Don't change the decoration
+    #[default]
     INHERIT,
     /// No decoration
     NONE,
@@ -51,12 +93,91 @@ pub enum Decoration {
 }
 
 /// Boolean value with 3 states
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Feature {
     /// Don't change the value
+    #[default]
     INHERIT,
     /// Enabled
     ENABLED,
     /// Disabled
     DISABLED,
 }
+
+/// Names of the colors, as used when parsing and displaying styles
+const COLOR_NAMES: [(Color, &str); 17] = [
+    (Color::INHERIT, "inherit"),
+    (Color::BLACK, "black"),
+    (Color::RED, "red"),
+    (Color::YELLOW, "yellow"),
+    (Color::GREEN, "green"),
+    (Color::CYAN, "cyan"),
+    (Color::BLUE, "blue"),
+    (Color::PURPLE, "purple"),
+    (Color::LIGHTGREY, "lightgrey"),
+    (Color::DARKGREY, "darkgrey"),
+    (Color::LIGHTRED, "lightred"),
+    (Color::LIGHTYELLOW, "lightyellow"),
+    (Color::LIGHTGREEN, "lightgreen"),
+    (Color::LIGHTCYAN, "lightcyan"),
+    (Color::LIGHTBLUE, "lightblue"),
+    (Color::LIGHTPURPLE, "lightpurple"),
+    (Color::WHITE, "white"),
+];
+
+impl fmt::Display for Color {
+    /// Writes the color in the format accepted by [Color::from_str()]
+    ///
+    /// The first 16 colors of the palette are written with their name, so
+    /// `FIXED(1)` is written `red`, like [Color::RED].
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FIXED(index) if *index < 16 => Self::palette(*index).fmt(f),
+            Self::FIXED(index) => write!(f, "{}", index),
+            Self::RGB(r, g, b) => write!(f, "#{:02x}{:02x}{:02x}", r, g, b),
+            _ => match COLOR_NAMES.iter().find(|(color, _)| color == self) {
+                Some((_, name)) => f.write_str(name),
+                None => write!(f, "{:?}", self),
+            },
+        }
+    }
+}
+
+impl FromStr for Color {
+    type Err = ParseStyleError;
+
+    /// Parses a color
+    ///
+    /// Colors are written as their lowercase name like `red` or `lightgrey`,
+    /// as an index in the 256-color palette like `208` (the first 16 indices
+    /// give the named colors, see [Color::palette()]), or in hexadecimal
+    /// notation like `#ff8700`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((color, _)) = COLOR_NAMES.iter().find(|(_, name)| *name == s) {
+            return Ok(*color);
+        }
+
+        let parsed = match s.strip_prefix('#') {
+            Some(hex) => parse_hex(hex),
+            None if s.bytes().all(|b| b.is_ascii_digit()) => s.parse().ok().map(Self::palette),
+            None => None,
+        };
+
+        parsed.ok_or_else(|| ParseStyleError::new(s))
+    }
+}
+
+/// Parses a `rrggbb` hexadecimal color
+fn parse_hex(hex: &str) -> Option<Color> {
+    if hex.len() != 6 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let value = u32::from_str_radix(hex, 16).ok()?;
+    Some(Color::RGB(
+        (value >> 16) as u8,
+        (value >> 8) as u8,
+        value as u8,
+    ))
+}
@@ -0,0 +1,103 @@
+/// Characters used to draw borders and grid lines
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Border {
+    /// Horizontal line
+    pub horizontal: char,
+    /// Vertical line
+    pub vertical: char,
+    /// Top left corner
+    pub top_left: char,
+    /// Top right corner
+    pub top_right: char,
+    /// Bottom left corner
+    pub bottom_left: char,
+    /// Bottom right corner
+    pub bottom_right: char,
+    /// Junction of a vertical line with the top border
+    pub top_tee: char,
+    /// Junction of a vertical line with the bottom border
+    pub bottom_tee: char,
+    /// Junction of a horizontal line with the left border
+    pub left_tee: char,
+    /// Junction of a horizontal line with the right border
+    pub right_tee: char,
+    /// Crossing of a horizontal and a vertical line
+    pub cross: char,
+}
+
+impl Border {
+    /// Thin lines, like `┌─┐`
+    pub const SINGLE: Self = Self {
+        horizontal: '─',
+        vertical: '│',
+        top_left: '┌',
+        top_right: '┐',
+        bottom_left: '└',
+        bottom_right: '┘',
+        top_tee: '┬',
+        bottom_tee: '┴',
+        left_tee: '├',
+        right_tee: '┤',
+        cross: '┼',
+    };
+
+    /// Thin lines with rounded corners, like `╭─╮`
+    pub const ROUNDED: Self = Self {
+        top_left: '╭',
+        top_right: '╮',
+        bottom_left: '╰',
+        bottom_right: '╯',
+        ..Self::SINGLE
+    };
+
+    /// Double lines, like `╔═╗`
+    pub const DOUBLE: Self = Self {
+        horizontal: '═',
+        vertical: '║',
+        top_left: '╔',
+        top_right: '╗',
+        bottom_left: '╚',
+        bottom_right: '╝',
+        top_tee: '╦',
+        bottom_tee: '╩',
+        left_tee: '╠',
+        right_tee: '╣',
+        cross: '╬',
+    };
+
+    /// Thick lines, like `┏━┓`
+    pub const HEAVY: Self = Self {
+        horizontal: '━',
+        vertical: '┃',
+        top_left: '┏',
+        top_right: '┓',
+        bottom_left: '┗',
+        bottom_right: '┛',
+        top_tee: '┳',
+        bottom_tee: '┻',
+        left_tee: '┣',
+        right_tee: '┫',
+        cross: '╋',
+    };
+
+    /// ASCII characters only, like `+-+`
+    pub const ASCII: Self = Self {
+        horizontal: '-',
+        vertical: '|',
+        top_left: '+',
+        top_right: '+',
+        bottom_left: '+',
+        bottom_right: '+',
+        top_tee: '+',
+        bottom_tee: '+',
+        left_tee: '+',
+        right_tee: '+',
+        cross: '+',
+    };
+}
+
+impl Default for Border {
+    fn default() -> Self {
+        Self::SINGLE
+    }
+}
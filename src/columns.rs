@@ -0,0 +1,241 @@
+use crate::{Alignment, RichString, RichText, Style};
+
+/// How the width of a column is chosen
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Constraint {
+    /// Exactly this number of columns
+    FIXED(usize),
+    /// As wide as the content, if there is enough space
+    CONTENT,
+    /// A share of the space left by the other columns
+    RATIO(usize),
+    /// One share of the space left, but at least this number of columns
+    MIN(usize),
+    /// One share of the space left, but at most this number of columns
+    MAX(usize),
+}
+
+/// Blocks of text arranged side by side
+///
+/// The content of each block is wrapped to the width of its column, and
+/// shorter blocks are padded so that all of them have the same height.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Columns {
+    columns: Vec<(RichText, Constraint)>,
+    width: usize,
+    gutter: usize,
+}
+
+impl Columns {
+    /// Creates an empty layout, taking a number of columns of the terminal
+    pub fn new(width: usize) -> Self {
+        Self {
+            columns: Vec::new(),
+            width,
+            gutter: 2,
+        }
+    }
+
+    /// Adds a column on the right
+    pub fn push<T: Into<RichText>>(&mut self, content: T, constraint: Constraint) {
+        self.columns.push((content.into(), constraint));
+    }
+
+    /// See [Self::push()].
+    pub fn column<T: Into<RichText>>(mut self, content: T, constraint: Constraint) -> Self {
+        self.push(content, constraint);
+        self
+    }
+
+    /// Sets the number of spaces between columns, 2 by default
+    pub fn gutter(mut self, gutter: usize) -> Self {
+        self.gutter = gutter;
+        self
+    }
+
+    /// Computes the width of each column
+    pub fn widths(&self) -> Vec<usize> {
+        let gutters = self.gutter * self.columns.len().saturating_sub(1);
+        let mut available = self.width.saturating_sub(gutters);
+        let mut widths = vec![None; self.columns.len()];
+
+        for (width, (content, constraint)) in widths.iter_mut().zip(&self.columns) {
+            let fixed = match constraint {
+                Constraint::FIXED(fixed) => *fixed,
+                Constraint::CONTENT => content.width(),
+                _ => continue,
+            };
+            *width = Some(fixed.min(available));
+            available -= fixed.min(available);
+        }
+
+        // columns that get less than their minimum or more than their
+        // maximum are fixed to it, until the shares fit all the bounds
+        loop {
+            let shares: usize = self.flexible(&widths).map(|(_, weight)| weight).sum();
+            let mut changed = false;
+
+            for (i, weight) in self.flexible(&widths).collect::<Vec<_>>() {
+                let share = available * weight / shares.max(1);
+                let bound = match self.columns[i].1 {
+                    Constraint::MIN(min) if share < min => min.min(available),
+                    Constraint::MAX(max) if share > max => max,
+                    _ => continue,
+                };
+                widths[i] = Some(bound);
+                available -= bound;
+                changed = true;
+                break;
+            }
+
+            if !changed {
+                break;
+            }
+        }
+
+        // the remaining space is shared, and rounding errors go to the
+        // first columns
+        let flexible: Vec<_> = self.flexible(&widths).collect();
+        let shares: usize = flexible.iter().map(|(_, weight)| weight).sum();
+        let mut remainder = available;
+        for (i, weight) in &flexible {
+            let share = available * weight / shares.max(1);
+            widths[*i] = Some(share);
+            remainder -= share;
+        }
+        for (i, _) in flexible.iter().take(remainder) {
+            widths[*i] = widths[*i].map(|width| width + 1);
+        }
+
+        widths.into_iter().map(|width| width.unwrap_or(0)).collect()
+    }
+
+    /// Returns the columns that share the remaining space, with their weight
+    fn flexible<'a>(
+        &'a self,
+        widths: &'a [Option<usize>],
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        self.columns
+            .iter()
+            .enumerate()
+            .filter(move |(i, _)| widths[*i].is_none())
+            .map(|(i, (_, constraint))| match constraint {
+                Constraint::RATIO(ratio) => (i, *ratio),
+                _ => (i, 1),
+            })
+    }
+
+    /// Renders the columns
+    ///
+    /// Columns with content are at least one column wide, even if the
+    /// result is then wider than the layout, so that no text is lost.
+    pub fn render(&self) -> RichText {
+        let widths: Vec<usize> = self
+            .widths()
+            .into_iter()
+            .zip(&self.columns)
+            .map(|(width, (content, _))| match content.width() {
+                0 => width,
+                _ => width.max(1),
+            })
+            .collect();
+        let blocks: Vec<RichText> = self
+            .columns
+            .iter()
+            .zip(&widths)
+            .map(|((content, _), width)| content.wrap(*width).align(*width, Alignment::LEFT))
+            .collect();
+        let height = blocks.iter().map(RichText::height).max().unwrap_or(0);
+
+        let mut rendered = RichText::new();
+        for (i, (mut block, width)) in blocks.into_iter().zip(widths).enumerate() {
+            let blank = RichString::from(&" ".repeat(width), Style::plain());
+            while block.height() < height {
+                block.push_line(blank.clone());
+            }
+            if i > 0 {
+                let gutter = RichString::from(&" ".repeat(self.gutter), Style::plain());
+                rendered.push_beside(&(0..height).map(|_| gutter.clone()).collect());
+            }
+            rendered.push_beside(&block);
+        }
+
+        rendered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shortcuts::*;
+
+    fn raw(text: &RichText) -> Vec<&str> {
+        text.lines().iter().map(RichString::raw).collect()
+    }
+
+    #[test]
+    fn test_widths() {
+        let columns = Columns::new(32)
+            .column("abc", Constraint::FIXED(5))
+            .column("abcdef", Constraint::CONTENT)
+            .column("", Constraint::RATIO(2))
+            .column("", Constraint::RATIO(1));
+        assert_eq!(columns.widths(), vec![5, 6, 10, 5]);
+
+        let columns = Columns::new(20)
+            .gutter(0)
+            .column("", Constraint::MIN(12))
+            .column("", Constraint::MAX(3))
+            .column("", Constraint::RATIO(1));
+        assert_eq!(columns.widths(), vec![12, 3, 5]);
+
+        let columns = Columns::new(10)
+            .gutter(1)
+            .column("", Constraint::RATIO(1))
+            .column("", Constraint::RATIO(1))
+            .column("", Constraint::FIXED(20));
+        assert_eq!(columns.widths(), vec![0, 0, 8]);
+
+        let columns = Columns::new(11)
+            .gutter(0)
+            .column("", Constraint::RATIO(1))
+            .column("", Constraint::RATIO(1))
+            .column("", Constraint::RATIO(1));
+        assert_eq!(columns.widths(), vec![4, 4, 3]);
+
+        assert!(Columns::new(10).widths().is_empty());
+    }
+
+    #[test]
+    fn test_render() {
+        let text = Columns::new(19)
+            .gutter(3)
+            .column(
+                RichString::from("the quick brown fox", RED),
+                Constraint::RATIO(1),
+            )
+            .column("a\nb", Constraint::FIXED(1))
+            .column("jumps", Constraint::RATIO(1))
+            .render();
+
+        assert_eq!(
+            raw(&text),
+            vec![
+                "the      a   jumps ",
+                "quick    b         ",
+                "brown              ",
+                "fox                ",
+            ]
+        );
+        assert_eq!(text.lines()[1].style_at(0), RED);
+        assert_eq!(text.lines()[1].style_at(6), Style::plain());
+
+        let text = Columns::new(4)
+            .gutter(1)
+            .column("ab", Constraint::FIXED(0))
+            .column("", Constraint::FIXED(0))
+            .column("cd", Constraint::RATIO(1))
+            .render();
+        assert_eq!(raw(&text), vec!["a  cd", "b    "]);
+    }
+}
@@ -0,0 +1,653 @@
+use crate::{RichString, RichText, Style, Theme};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::ops::Range;
+use unicode_width::UnicodeWidthChar;
+
+/// How serious a diagnostic or a label is
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Something is wrong
+    ERROR,
+    /// Something is probably wrong
+    WARNING,
+    /// Additional information
+    NOTE,
+    /// A suggestion to fix the problem
+    HELP,
+}
+
+impl Severity {
+    /// Returns the name of the style of this severity in a theme, like
+    /// `diagnostic.error`
+    pub fn style_name(self) -> &'static str {
+        match self {
+            Self::ERROR => "diagnostic.error",
+            Self::WARNING => "diagnostic.warning",
+            Self::NOTE => "diagnostic.note",
+            Self::HELP => "diagnostic.help",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::ERROR => "error",
+            Self::WARNING => "warning",
+            Self::NOTE => "note",
+            Self::HELP => "help",
+        })
+    }
+}
+
+/// A message attached to a part of the source
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Label {
+    range: Range<usize>,
+    severity: Severity,
+    message: String,
+}
+
+/// An error or a warning about some source text, in the style of rustc
+///
+/// ```text
+/// error[E0308]: mismatched types
+///  --> main.dsl:1:9
+///   |
+/// 1 | let x = "a" + 1;
+///   |         ^^^   - number
+///   |         |
+///   |         string
+///   = note: strings and numbers can't be added
+/// ```
+///
+/// The styles come from a theme, with the names `diagnostic.error`,
+/// `diagnostic.warning`, `diagnostic.note` and `diagnostic.help` for the
+/// severities, `diagnostic.message` for the main message and
+/// `diagnostic.gutter` for line numbers and guides.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    severity: Severity,
+    code: Option<String>,
+    message: String,
+    labels: Vec<Label>,
+    notes: Vec<(Severity, String)>,
+}
+
+impl Diagnostic {
+    /// Creates a diagnostic with a main message
+    pub fn new(severity: Severity, message: &str) -> Self {
+        Self {
+            severity,
+            code: None,
+            message: String::from(message),
+            labels: Vec::new(),
+            notes: Vec::new(),
+        }
+    }
+
+    /// Sets a code identifying the kind of problem, like `E0308`
+    pub fn code(mut self, code: &str) -> Self {
+        self.code = Some(String::from(code));
+        self
+    }
+
+    /// Adds a message under a part of the source, given in bytes
+    ///
+    /// The first label gives the location of the diagnostic. A range can
+    /// span several lines. Labels with the severity of the diagnostic are
+    /// underlined with `^`, and the others with `-`. Ranges that go past
+    /// the end of the source are cut there.
+    pub fn label(mut self, range: Range<usize>, severity: Severity, message: &str) -> Self {
+        self.labels.push(Label {
+            range,
+            severity,
+            message: String::from(message),
+        });
+        self
+    }
+
+    /// Adds a note at the end
+    pub fn note(mut self, note: &str) -> Self {
+        self.notes.push((Severity::NOTE, String::from(note)));
+        self
+    }
+
+    /// Adds a suggestion at the end
+    pub fn help(mut self, help: &str) -> Self {
+        self.notes.push((Severity::HELP, String::from(help)));
+        self
+    }
+
+    /// Returns the default theme, with the colors of rustc
+    pub fn default_theme() -> Theme {
+        let style = |style: &str| style.parse::<Style>().unwrap_or_default();
+
+        Theme::new()
+            .with("diagnostic.error", style("bold lightred"))
+            .with("diagnostic.warning", style("bold lightyellow"))
+            .with("diagnostic.note", style("bold lightgreen"))
+            .with("diagnostic.help", style("bold lightcyan"))
+            .with("diagnostic.message", style("bold"))
+            .with("diagnostic.gutter", style("bold lightblue"))
+    }
+
+    /// Renders the diagnostic with the default theme
+    ///
+    /// `name` is the name of the source, usually a path, and can be empty.
+    pub fn render(&self, name: &str, source: &str) -> RichString {
+        self.render_with(name, source, &Self::default_theme())
+    }
+
+    /// Renders the diagnostic with the styles of a theme
+    pub fn render_with(&self, name: &str, source: &str, theme: &Theme) -> RichString {
+        let snippet = Snippet::new(source, &self.labels, self.severity);
+        let gutter = snippet
+            .displayed
+            .iter()
+            .last()
+            .map_or(1, |line| (line + 1).to_string().len());
+        let mut text = RichText::new();
+
+        let mut header = self.severity.to_string();
+        if let Some(code) = &self.code {
+            header.push_str(&format!("[{}]", code));
+        }
+        text.push_line(
+            RichString::named(&header, self.severity.style_name())
+                + RichString::named(&format!(": {}", self.message), "diagnostic.message"),
+        );
+
+        if !name.is_empty() {
+            let location = match snippet.labels.first() {
+                Some(label) => format!(
+                    "{}:{}:{}",
+                    name,
+                    label.start_line + 1,
+                    label.start_column + 1
+                ),
+                None => String::from(name),
+            };
+            text.push_line(
+                RichString::named(&format!("{}--> ", " ".repeat(gutter)), "diagnostic.gutter")
+                    + location.as_str(),
+            );
+        }
+
+        if !snippet.labels.is_empty() {
+            let margin =
+                RichString::named(&format!("{} |", " ".repeat(gutter)), "diagnostic.gutter");
+            text.push_line(margin.clone());
+
+            let mut previous = None;
+            for &line in &snippet.displayed {
+                if previous.is_some_and(|previous| line > previous + 1) {
+                    text.push_line(RichString::named("...", "diagnostic.gutter"));
+                }
+                previous = Some(line);
+
+                let number = format!("{:>width$} | ", line + 1, width = gutter);
+                text.push_line(
+                    RichString::named(&number, "diagnostic.gutter")
+                        + snippet.source_row(line).into_string()
+                        + expand_tabs(snippet.lines[line]).as_str(),
+                );
+                for row in snippet.annotation_rows(line) {
+                    text.push_line(margin.clone() + " " + row.into_string());
+                }
+            }
+        }
+
+        for (severity, note) in &self.notes {
+            text.push_line(
+                RichString::named(&format!("{} = ", " ".repeat(gutter)), "diagnostic.gutter")
+                    + RichString::named(&format!("{}:", severity), "diagnostic.message")
+                    + format!(" {}", note).as_str(),
+            );
+        }
+
+        let rendered: RichString = text.into();
+        rendered.into_resolved(theme)
+    }
+}
+
+/// A label placed in the source
+struct Located<'a> {
+    start_line: usize,
+    start_column: usize,
+    end_line: usize,
+    /// Column after the label for single-line labels, or of the last
+    /// character for multi-line labels
+    end_column: usize,
+    /// Column of the bracket of multi-line labels
+    slot: Option<usize>,
+    severity: Severity,
+    message: &'a str,
+}
+
+/// The source and the labels placed in it
+struct Snippet<'a> {
+    lines: Vec<&'a str>,
+    labels: Vec<Located<'a>>,
+    displayed: BTreeSet<usize>,
+    /// Number of multi-line labels
+    slots: usize,
+    /// Labels with this severity are underlined with `^`, others with `-`
+    severity: Severity,
+}
+
+impl<'a> Snippet<'a> {
+    fn new(source: &'a str, labels: &'a [Label], severity: Severity) -> Self {
+        let lines: Vec<&str> = source
+            .split('\n')
+            .map(|line| line.strip_suffix('\r').unwrap_or(line))
+            .collect();
+        let mut starts = vec![0];
+        starts.extend(source.match_indices('\n').map(|(i, _)| i + 1));
+
+        let locate = |byte: usize| {
+            let mut byte = byte.min(source.len());
+            while !source.is_char_boundary(byte) {
+                byte -= 1;
+            }
+            let line = starts.partition_point(|start| *start <= byte) - 1;
+            let prefix = &source[starts[line]..byte];
+            (
+                line,
+                display_width(prefix.strip_suffix('\r').unwrap_or(prefix)),
+            )
+        };
+
+        let mut slots = 0;
+        let mut displayed = BTreeSet::new();
+        let labels = labels
+            .iter()
+            .map(|label| {
+                let range = label.range.start.min(source.len())..label.range.end.min(source.len());
+                let (start_line, start_column) = locate(range.start);
+                let last = range.end.max(range.start.saturating_add(1)) - 1;
+                let (end_line, _) = locate(last);
+
+                let mut located = Located {
+                    start_line,
+                    start_column,
+                    end_line: start_line,
+                    end_column: locate(range.end).1,
+                    slot: None,
+                    severity: label.severity,
+                    message: &label.message,
+                };
+                displayed.insert(start_line);
+
+                if end_line > start_line {
+                    located.end_line = end_line;
+                    located.end_column = locate(last).1;
+                    located.slot = Some(slots);
+                    slots += 1;
+                    displayed.extend([start_line + 1, end_line - 1, end_line]);
+                } else {
+                    if locate(range.end).0 != start_line {
+                        // the range ends with the newline
+                        located.end_column = display_width(lines[start_line]) + 1;
+                    }
+                    located.end_column = located.end_column.max(start_column + 1);
+                }
+
+                located
+            })
+            .collect();
+
+        Self {
+            lines,
+            labels,
+            displayed,
+            slots,
+            severity,
+        }
+    }
+
+    /// Returns the width taken by the brackets of multi-line labels
+    fn area(&self) -> usize {
+        match self.slots {
+            0 => 0,
+            slots => slots + 1,
+        }
+    }
+
+    /// Returns the multi-line labels
+    fn multi_line(&self) -> impl Iterator<Item = (usize, &Located<'a>)> {
+        self.labels
+            .iter()
+            .filter_map(|label| label.slot.map(|slot| (slot, label)))
+    }
+
+    /// Returns the brackets before a line of source
+    fn source_row(&self, line: usize) -> Row {
+        let mut row = Row::default();
+        for (slot, label) in self.multi_line() {
+            if label.start_line < line && line <= label.end_line {
+                row.put(slot, '|', label.severity.style_name());
+            }
+        }
+        if self.area() > 0 {
+            row.pad(self.area());
+        }
+        row
+    }
+
+    /// Returns the brackets of multi-line labels going through a line,
+    /// except those that are `closed`
+    fn brackets(&self, line: usize, closed: usize) -> Row {
+        let mut row = Row::default();
+        for (slot, label) in self.multi_line() {
+            let ended = label.end_line == line && slot < closed;
+            if label.start_line <= line && line <= label.end_line && !ended {
+                row.put(slot, '|', label.severity.style_name());
+            }
+        }
+        row
+    }
+
+    /// Returns the rows of underlines and messages below a line of source
+    fn annotation_rows(&self, line: usize) -> Vec<Row> {
+        let area = self.area();
+        let mut rows = Vec::new();
+
+        for (slot, label) in self
+            .multi_line()
+            .filter(|(_, label)| label.start_line == line)
+        {
+            let name = label.severity.style_name();
+            let mut row = self.brackets(line, 0);
+            row.clear(slot);
+            for column in slot + 1..area + label.start_column {
+                row.put(column, '_', name);
+            }
+            row.put(area + label.start_column, '^', name);
+            rows.push(row);
+        }
+
+        let mut single: Vec<&Located> = self
+            .labels
+            .iter()
+            .filter(|label| label.slot.is_none() && label.start_line == line)
+            .collect();
+        single.sort_by_key(|label| label.start_column);
+
+        if let Some(last) = single.last() {
+            let mut row = self.brackets(line, 0);
+            for label in &single {
+                let underline = if label.severity == self.severity {
+                    '^'
+                } else {
+                    '-'
+                };
+                for column in label.start_column..label.end_column {
+                    row.put(area + column, underline, label.severity.style_name());
+                }
+            }
+            if !last.message.is_empty() {
+                let end = single
+                    .iter()
+                    .map(|label| label.end_column)
+                    .max()
+                    .unwrap_or(0);
+                row.message(area + end + 1, last.message, last.severity.style_name());
+            }
+            rows.push(row);
+
+            for (i, label) in single.iter().enumerate().rev().skip(1) {
+                if label.message.is_empty() {
+                    continue;
+                }
+                let pending: Vec<&&Located> = single[..i]
+                    .iter()
+                    .filter(|label| !label.message.is_empty())
+                    .collect();
+
+                let mut row = self.brackets(line, 0);
+                for pending in pending.iter().chain([&label]) {
+                    row.put(
+                        area + pending.start_column,
+                        '|',
+                        pending.severity.style_name(),
+                    );
+                }
+                rows.push(row);
+
+                let mut row = self.brackets(line, 0);
+                for pending in &pending {
+                    row.put(
+                        area + pending.start_column,
+                        '|',
+                        pending.severity.style_name(),
+                    );
+                }
+                row.message(
+                    area + label.start_column,
+                    label.message,
+                    label.severity.style_name(),
+                );
+                rows.push(row);
+            }
+        }
+
+        for (slot, label) in self
+            .multi_line()
+            .filter(|(_, label)| label.end_line == line)
+        {
+            let name = label.severity.style_name();
+            let mut row = self.brackets(line, slot);
+            row.put(slot, '|', name);
+            for column in slot + 1..area + label.end_column {
+                row.put(column, '_', name);
+            }
+            row.put(area + label.end_column, '^', name);
+            if !label.message.is_empty() {
+                row.message(area + label.end_column + 2, label.message, name);
+            }
+            rows.push(row);
+        }
+
+        rows
+    }
+}
+
+/// A line of annotations, built column by column
+#[derive(Default)]
+struct Row {
+    cells: Vec<Option<(char, &'static str)>>,
+    message: Option<(usize, String, &'static str)>,
+}
+
+impl Row {
+    fn put(&mut self, column: usize, c: char, name: &'static str) {
+        self.pad(column + 1);
+        self.cells[column] = Some((c, name));
+    }
+
+    fn clear(&mut self, column: usize) {
+        if let Some(cell) = self.cells.get_mut(column) {
+            *cell = None;
+        }
+    }
+
+    fn pad(&mut self, width: usize) {
+        if self.cells.len() < width {
+            self.cells.resize(width, None);
+        }
+    }
+
+    /// Places a message at a column, after every cell
+    fn message(&mut self, column: usize, message: &str, name: &'static str) {
+        self.message = Some((column, String::from(message), name));
+    }
+
+    fn into_string(mut self) -> RichString {
+        if let Some((column, _, _)) = self.message {
+            self.pad(column);
+            self.cells.truncate(column);
+        }
+
+        let mut string = RichString::new();
+        let mut run = String::new();
+        let mut current = None;
+
+        for cell in self.cells {
+            let (c, name) = cell.map_or((' ', None), |(c, name)| (c, Some(name)));
+            if name != current && !run.is_empty() {
+                string.push(&styled(&run, current));
+                run.clear();
+            }
+            current = name;
+            run.push(c);
+        }
+        string.push(&styled(&run, current));
+
+        if let Some((_, message, name)) = self.message {
+            string.push(&RichString::named(&message, name));
+        }
+        string
+    }
+}
+
+fn styled(text: &str, name: Option<&str>) -> RichString {
+    match name {
+        Some(name) => RichString::named(text, name),
+        None => RichString::from(text, Style::plain()),
+    }
+}
+
+/// Returns the width of text, tabs counting as four columns
+fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| match c {
+            '\t' => 4,
+            _ => c.width().unwrap_or(0),
+        })
+        .sum()
+}
+
+fn expand_tabs(text: &str) -> String {
+    text.replace('\t', "    ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw(string: &RichString) -> Vec<&str> {
+        string.raw().split('\n').collect()
+    }
+
+    #[test]
+    fn test_render() {
+        let diagnostic = Diagnostic::new(Severity::ERROR, "mismatched types")
+            .code("E0308")
+            .label(8..11, Severity::ERROR, "string")
+            .label(14..15, Severity::NOTE, "number")
+            .note("strings and numbers can't be added");
+        let rendered = diagnostic.render("main.dsl", "let x = \"a\" + 1;\n");
+
+        assert_eq!(
+            raw(&rendered),
+            vec![
+                "error[E0308]: mismatched types",
+                " --> main.dsl:1:9",
+                "  |",
+                "1 | let x = \"a\" + 1;",
+                "  |         ^^^   - number",
+                "  |         |",
+                "  |         string",
+                "  = note: strings and numbers can't be added",
+            ]
+        );
+
+        let theme = Diagnostic::default_theme();
+        assert_eq!(rendered.style_at(0), theme.get("diagnostic.error"));
+        assert_eq!(rendered.style_at(14), theme.get("diagnostic.message"));
+        let caret = rendered.raw().find('^').unwrap();
+        assert_eq!(rendered.style_at(caret), theme.get("diagnostic.error"));
+        let dash = rendered.raw().find('-').unwrap();
+        assert_eq!(rendered.style_at(dash), theme.get("diagnostic.gutter"));
+    }
+
+    #[test]
+    fn test_multi_line() {
+        let source = "fn main() {\n    let x = 1;\n    x\n}\n";
+        let diagnostic = Diagnostic::new(Severity::WARNING, "unused")
+            .label(10..source.len() - 1, Severity::WARNING, "this block")
+            .label(20..21, Severity::WARNING, "");
+        let rendered = diagnostic.render_with("", source, &Theme::new());
+
+        assert_eq!(
+            raw(&rendered),
+            vec![
+                "warning: unused",
+                "  |",
+                "1 |   fn main() {",
+                "  |  ___________^",
+                "2 | |     let x = 1;",
+                "  | |         ^",
+                "3 | |     x",
+                "4 | | }",
+                "  | |_^ this block",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_elided_lines() {
+        let source = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n";
+        let diagnostic = Diagnostic::new(Severity::ERROR, "long")
+            .label(0..17, Severity::ERROR, "here")
+            .help("make it shorter");
+        let rendered = diagnostic.render_with("test", source, &Theme::new());
+
+        assert_eq!(
+            raw(&rendered),
+            vec![
+                "error: long",
+                " --> test:1:1",
+                "  |",
+                "1 |   a",
+                "  |  _^",
+                "2 | | b",
+                "...",
+                "8 | | h",
+                "9 | | i",
+                "  | |_^ here",
+                "  = help: make it shorter",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_without_labels() {
+        let diagnostic = Diagnostic::new(Severity::NOTE, "nothing to do").note("all good");
+        assert_eq!(
+            raw(&diagnostic.render("", "")),
+            vec!["note: nothing to do", "  = note: all good"]
+        );
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        let diagnostic = Diagnostic::new(Severity::ERROR, "unexpected end")
+            .label(usize::MAX..usize::MAX, Severity::ERROR, "here")
+            .label(4..usize::MAX, Severity::NOTE, "rest");
+        assert_eq!(
+            raw(&diagnostic.render("main.dsl", "let x")),
+            vec![
+                "error: unexpected end",
+                " --> main.dsl:1:6",
+                "  |",
+                "1 | let x",
+                "  |     -^ here",
+                "  |     |",
+                "  |     rest",
+            ]
+        );
+    }
+}
@@ -0,0 +1,719 @@
+use crate::{Alignment, RichString, Style, Theme};
+use std::ops::Range;
+
+/// An operation turning a sequence into another
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Op {
+    /// Items at these indices are the same in both sequences
+    Equal(usize, usize),
+    /// An item of the old sequence is removed
+    Delete(usize),
+    /// An item of the new sequence is added
+    Insert(usize),
+}
+
+/// Computes the shortest edit script between two sequences, with the
+/// algorithm of Myers
+///
+/// This is the linear space version, which splits the sequences around
+/// the middle of the script and recurses on both halves. Within a change,
+/// removed items come before added items.
+fn edit_script<T: PartialEq>(old: &[T], new: &[T]) -> Vec<Op> {
+    let max_d = (old.len() + new.len()).div_ceil(2) + 1;
+    let mut forward = Diagonals::new(max_d);
+    let mut backward = Diagonals::new(max_d);
+    let mut ops = Vec::new();
+
+    conquer(
+        old,
+        0..old.len(),
+        new,
+        0..new.len(),
+        &mut forward,
+        &mut backward,
+        &mut ops,
+    );
+
+    // put the removed items first in each change
+    let mut start = 0;
+    while start < ops.len() {
+        let end = ops[start..]
+            .iter()
+            .position(|op| matches!(op, Op::Equal(..)))
+            .map_or(ops.len(), |position| start + position);
+        ops[start..end].sort_by_key(|op| matches!(op, Op::Insert(_)));
+        start = end + 1;
+    }
+
+    ops
+}
+
+/// The furthest point reached on each diagonal `k`, which can be negative
+struct Diagonals {
+    offset: isize,
+    x: Vec<usize>,
+}
+
+impl Diagonals {
+    fn new(max_d: usize) -> Self {
+        Self {
+            offset: max_d as isize,
+            x: vec![0; 2 * max_d + 1],
+        }
+    }
+
+    fn get(&self, k: isize) -> usize {
+        self.x[(self.offset + k) as usize]
+    }
+
+    fn set(&mut self, k: isize, x: usize) {
+        self.x[(self.offset + k) as usize] = x;
+    }
+}
+
+/// Appends the edit script between two ranges of the sequences
+fn conquer<T: PartialEq>(
+    old: &[T],
+    mut old_range: Range<usize>,
+    new: &[T],
+    mut new_range: Range<usize>,
+    forward: &mut Diagonals,
+    backward: &mut Diagonals,
+    ops: &mut Vec<Op>,
+) {
+    while !old_range.is_empty()
+        && !new_range.is_empty()
+        && old[old_range.start] == new[new_range.start]
+    {
+        ops.push(Op::Equal(old_range.start, new_range.start));
+        old_range.start += 1;
+        new_range.start += 1;
+    }
+    let mut suffix = 0;
+    while suffix < old_range.len()
+        && suffix < new_range.len()
+        && old[old_range.end - suffix - 1] == new[new_range.end - suffix - 1]
+    {
+        suffix += 1;
+    }
+    old_range.end -= suffix;
+    new_range.end -= suffix;
+
+    if old_range.is_empty() || new_range.is_empty() {
+        ops.extend(old_range.clone().map(Op::Delete));
+        ops.extend(new_range.clone().map(Op::Insert));
+    } else {
+        let (x, y) = middle_snake(old, &old_range, new, &new_range, forward, backward);
+        conquer(
+            old,
+            old_range.start..x,
+            new,
+            new_range.start..y,
+            forward,
+            backward,
+            ops,
+        );
+        conquer(
+            old,
+            x..old_range.end,
+            new,
+            y..new_range.end,
+            forward,
+            backward,
+            ops,
+        );
+    }
+
+    ops.extend((0..suffix).map(|i| Op::Equal(old_range.end + i, new_range.end + i)));
+}
+
+/// Finds a point on a shortest edit script between two non-empty ranges,
+/// by searching from both ends until the searches meet
+fn middle_snake<T: PartialEq>(
+    old: &[T],
+    old_range: &Range<usize>,
+    new: &[T],
+    new_range: &Range<usize>,
+    forward: &mut Diagonals,
+    backward: &mut Diagonals,
+) -> (usize, usize) {
+    let old = &old[old_range.clone()];
+    let new = &new[new_range.clone()];
+    let (n, m) = (old.len(), new.len());
+    let delta = n as isize - m as isize;
+    let odd = delta % 2 != 0;
+    forward.set(1, 0);
+    backward.set(1, 0);
+
+    // the previous diagonal is the one that went the furthest
+    let from_above =
+        |v: &Diagonals, k: isize, d: isize| k == -d || (k != d && v.get(k - 1) < v.get(k + 1));
+
+    for d in 0..=(n + m).div_ceil(2) as isize {
+        for k in (-d..=d).rev().step_by(2) {
+            let mut x = if from_above(forward, k, d) {
+                forward.get(k + 1)
+            } else {
+                forward.get(k - 1) + 1
+            };
+            let start = (x, (x as isize - k) as usize);
+            let mut y = start.1;
+            while x < n && y < m && old[x] == new[y] {
+                x += 1;
+                y += 1;
+            }
+            forward.set(k, x);
+
+            if odd && (k - delta).abs() < d && x + backward.get(delta - k) >= n {
+                return (old_range.start + start.0, new_range.start + start.1);
+            }
+        }
+
+        for k in (-d..=d).rev().step_by(2) {
+            let mut x = if from_above(backward, k, d) {
+                backward.get(k + 1)
+            } else {
+                backward.get(k - 1) + 1
+            };
+            let mut y = (x as isize - k) as usize;
+            while x < n && y < m && old[n - x - 1] == new[m - y - 1] {
+                x += 1;
+                y += 1;
+            }
+            backward.set(k, x);
+
+            if !odd && (k - delta).abs() <= d && x + forward.get(delta - k) >= n {
+                return (old_range.start + n - x, new_range.start + m - y);
+            }
+        }
+    }
+
+    unreachable!("the searches always meet")
+}
+
+/// Splits a line into words, runs of whitespace and punctuation characters
+fn tokens(line: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+
+    let class = |c: char| {
+        if c.is_alphanumeric() || c == '_' {
+            0
+        } else if c.is_whitespace() {
+            1
+        } else {
+            2
+        }
+    };
+
+    let chars: Vec<(usize, char)> = line.char_indices().collect();
+    for (i, &(index, c)) in chars.iter().enumerate().skip(1) {
+        let previous = chars[i - 1].1;
+        if class(c) != class(previous) || class(c) == 2 {
+            tokens.push(&line[start..index]);
+            start = index;
+        }
+    }
+    if start < line.len() {
+        tokens.push(&line[start..]);
+    }
+
+    tokens
+}
+
+/// Line following one that ends its text without a line break
+const NO_NEWLINE: &str = "\\ No newline at end of file";
+
+/// Returns a line without its line break, with a carriage return shown as
+/// `^M`
+fn display(line: &str) -> String {
+    let line = line.strip_suffix('\n').unwrap_or(line);
+    match line.strip_suffix('\r') {
+        Some(line) => format!("{}^M", line),
+        None => String::from(line),
+    }
+}
+
+/// Part of a hunk
+///
+/// The flags tell if the last line ends its text without a line break.
+enum Block {
+    /// A line that didn't change
+    Context(RichString, bool),
+    /// Lines that were removed and the lines that replaced them
+    Change(Vec<RichString>, bool, Vec<RichString>, bool),
+}
+
+/// A group of changes, with lines of context around them
+struct Hunk {
+    header: String,
+    blocks: Vec<Block>,
+}
+
+/// The differences between two texts, line by line
+///
+/// Lines that changed are compared word by word, so that the changed words
+/// can be highlighted. The styles come from a theme, with the names
+/// `diff.delete` and `diff.insert` for the lines, `diff.delete.word` and
+/// `diff.insert.word` for the changed words, and `diff.hunk` for the
+/// headers of hunks.
+///
+/// Lines are compared with their line break, so a missing line break at
+/// the end of a text or a change from `\n` to `\r\n` is a difference. A
+/// carriage return at the end of a line is shown as `^M`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diff {
+    old: Vec<String>,
+    new: Vec<String>,
+    ops: Vec<Op>,
+    context: usize,
+}
+
+impl Diff {
+    /// Compares two texts
+    pub fn new(old: &str, new: &str) -> Self {
+        let old: Vec<String> = old.split_inclusive('\n').map(String::from).collect();
+        let new: Vec<String> = new.split_inclusive('\n').map(String::from).collect();
+        let ops = edit_script(&old, &new);
+
+        Self {
+            old,
+            new,
+            ops,
+            context: 3,
+        }
+    }
+
+    /// Sets the number of unchanged lines shown around changes, 3 by default
+    pub fn context(mut self, lines: usize) -> Self {
+        self.context = lines;
+        self
+    }
+
+    /// Returns `true` if both texts have the same lines
+    pub fn is_empty(&self) -> bool {
+        self.ops.iter().all(|op| matches!(op, Op::Equal(..)))
+    }
+
+    /// Returns the default theme, with red and green lines
+    pub fn default_theme() -> Theme {
+        let style = |style: &str| style.parse::<Style>().unwrap_or_default();
+
+        Theme::new()
+            .with("diff.delete", style("red"))
+            .with("diff.insert", style("green"))
+            .with("diff.delete.word", style("on_52"))
+            .with("diff.insert.word", style("on_22"))
+            .with("diff.hunk", style("cyan"))
+    }
+
+    /// Renders the diff in the unified format, with the default theme
+    pub fn unified(&self) -> Vec<RichString> {
+        self.unified_with(&Self::default_theme())
+    }
+
+    /// Renders the diff in the unified format, with the styles of a theme
+    ///
+    /// Removed lines start with `-` and added lines with `+`. A line that
+    /// ends its text without a line break is followed by
+    /// `\ No newline at end of file`.
+    pub fn unified_with(&self, theme: &Theme) -> Vec<RichString> {
+        let mut lines = Vec::new();
+
+        for hunk in self.hunks() {
+            lines.push(RichString::named(&hunk.header, "diff.hunk"));
+            for block in hunk.blocks {
+                match block {
+                    Block::Context(line, no_newline) => {
+                        lines.push(" " + line);
+                        if no_newline {
+                            lines.push(RichString::from(NO_NEWLINE, Style::plain()));
+                        }
+                    }
+                    Block::Change(deleted, old_no_newline, inserted, new_no_newline) => {
+                        lines.extend(
+                            deleted
+                                .into_iter()
+                                .map(|line| RichString::named("-", "diff.delete") + line),
+                        );
+                        if old_no_newline {
+                            lines.push(RichString::from(NO_NEWLINE, Style::plain()));
+                        }
+                        lines.extend(
+                            inserted
+                                .into_iter()
+                                .map(|line| RichString::named("+", "diff.insert") + line),
+                        );
+                        if new_no_newline {
+                            lines.push(RichString::from(NO_NEWLINE, Style::plain()));
+                        }
+                    }
+                }
+            }
+        }
+
+        lines
+            .into_iter()
+            .map(|line| line.into_resolved(theme))
+            .collect()
+    }
+
+    /// Renders the old and new texts side by side, with the default theme
+    pub fn side_by_side(&self, width: usize) -> Vec<RichString> {
+        self.side_by_side_with(width, &Self::default_theme())
+    }
+
+    /// Renders the old and new texts side by side, with the styles of a
+    /// theme
+    ///
+    /// Lines that are too long for their half of `width` are cut.
+    pub fn side_by_side_with(&self, width: usize, theme: &Theme) -> Vec<RichString> {
+        let column = width.saturating_sub(3) / 2;
+        let cell = |marker: &str, name: &str, line: Option<RichString>| match line {
+            Some(line) => (RichString::named(marker, name) + line)
+                .ellipsize(column)
+                .align(column, Alignment::LEFT),
+            None => RichString::from(&" ".repeat(column), Style::plain()),
+        };
+        let mut lines = Vec::new();
+
+        for hunk in self.hunks() {
+            lines.push(RichString::named(&hunk.header, "diff.hunk").ellipsize(width));
+            for block in hunk.blocks {
+                match block {
+                    Block::Context(line, _) => {
+                        let side = cell(" ", "", Some(line));
+                        lines.push(side.clone() + " │ " + side);
+                    }
+                    Block::Change(deleted, _, inserted, _) => {
+                        let mut deleted = deleted.into_iter();
+                        let mut inserted = inserted.into_iter();
+                        loop {
+                            let (old, new) = (deleted.next(), inserted.next());
+                            if old.is_none() && new.is_none() {
+                                break;
+                            }
+                            lines.push(
+                                cell("-", "diff.delete", old)
+                                    + " │ "
+                                    + cell("+", "diff.insert", new),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        lines
+            .into_iter()
+            .map(|line| line.into_resolved(theme))
+            .collect()
+    }
+
+    /// Groups the changes into hunks
+    fn hunks(&self) -> Vec<Hunk> {
+        let changes: Vec<usize> = (0..self.ops.len())
+            .filter(|i| !matches!(self.ops[*i], Op::Equal(..)))
+            .collect();
+        let mut ranges: Vec<(usize, usize)> = Vec::new();
+
+        for change in changes {
+            let start = change.saturating_sub(self.context);
+            let end = (change + self.context + 1).min(self.ops.len());
+            match ranges.last_mut() {
+                Some(last) if start <= last.1 => last.1 = end,
+                _ => ranges.push((start, end)),
+            }
+        }
+
+        ranges
+            .into_iter()
+            .map(|(start, end)| self.hunk(&self.ops[start..end]))
+            .collect()
+    }
+
+    /// Builds a hunk from its operations
+    fn hunk(&self, ops: &[Op]) -> Hunk {
+        let position = |op: &Op| match *op {
+            Op::Equal(old, new) => (old, new),
+            Op::Delete(old) => (old, self.position_in_new(old)),
+            Op::Insert(new) => (self.position_in_old(new), new),
+        };
+        let (old_start, new_start) = position(&ops[0]);
+        let old_count = ops.iter().filter(|op| !matches!(op, Op::Insert(_))).count();
+        let new_count = ops.iter().filter(|op| !matches!(op, Op::Delete(_))).count();
+        let range = |start: usize, count: usize| match count {
+            0 => format!("{},0", start),
+            _ => format!("{},{}", start + 1, count),
+        };
+
+        let mut blocks = Vec::new();
+        let mut deleted = Vec::new();
+        let mut inserted = Vec::new();
+        for op in ops.iter().chain([&Op::Equal(usize::MAX, usize::MAX)]) {
+            match *op {
+                Op::Delete(old) => deleted.push(old),
+                Op::Insert(new) => inserted.push(new),
+                Op::Equal(old, _) => {
+                    if !deleted.is_empty() || !inserted.is_empty() {
+                        blocks.push(self.change(&deleted, &inserted));
+                        deleted.clear();
+                        inserted.clear();
+                    }
+                    if let Some(line) = self.old.get(old) {
+                        blocks.push(Block::Context(
+                            RichString::from(&display(line), Style::plain()),
+                            !line.ends_with('\n'),
+                        ));
+                    }
+                }
+            }
+        }
+
+        Hunk {
+            header: format!(
+                "@@ -{} +{} @@",
+                range(old_start, old_count),
+                range(new_start, new_count)
+            ),
+            blocks,
+        }
+    }
+
+    /// Returns the number of new lines before an old line
+    fn position_in_new(&self, old: usize) -> usize {
+        self.ops
+            .iter()
+            .take_while(|op| !matches!(op, Op::Equal(o, _) | Op::Delete(o) if *o >= old))
+            .filter(|op| !matches!(op, Op::Delete(_)))
+            .count()
+    }
+
+    /// Returns the number of old lines before a new line
+    fn position_in_old(&self, new: usize) -> usize {
+        self.ops
+            .iter()
+            .take_while(|op| !matches!(op, Op::Equal(_, n) | Op::Insert(n) if *n >= new))
+            .filter(|op| !matches!(op, Op::Insert(_)))
+            .count()
+    }
+
+    /// Highlights the words that changed in a group of lines
+    ///
+    /// Removed and added lines are compared in order.
+    fn change(&self, deleted: &[usize], inserted: &[usize]) -> Block {
+        let mut old: Vec<RichString> = deleted
+            .iter()
+            .map(|i| RichString::named(&display(&self.old[*i]), "diff.delete"))
+            .collect();
+        let mut new: Vec<RichString> = inserted
+            .iter()
+            .map(|i| RichString::named(&display(&self.new[*i]), "diff.insert"))
+            .collect();
+
+        for (i, (o, n)) in deleted.iter().zip(inserted).enumerate() {
+            let (old_text, new_text) = (display(&self.old[*o]), display(&self.new[*n]));
+            let old_tokens = tokens(&old_text);
+            let new_tokens = tokens(&new_text);
+            let mut old_line = RichString::new();
+            let mut new_line = RichString::new();
+
+            for op in edit_script(&old_tokens, &new_tokens) {
+                match op {
+                    Op::Equal(o, n) => {
+                        old_line.push(&RichString::named(old_tokens[o], "diff.delete"));
+                        new_line.push(&RichString::named(new_tokens[n], "diff.insert"));
+                    }
+                    Op::Delete(o) => {
+                        old_line.push(&RichString::named(old_tokens[o], "diff.delete.word"))
+                    }
+                    Op::Insert(n) => {
+                        new_line.push(&RichString::named(new_tokens[n], "diff.insert.word"))
+                    }
+                }
+            }
+
+            old[i] = old_line;
+            new[i] = new_line;
+        }
+
+        let no_newline = |lines: &[String], indices: &[usize]| {
+            indices.last().is_some_and(|i| !lines[*i].ends_with('\n'))
+        };
+        Block::Change(
+            old,
+            no_newline(&self.old, deleted),
+            new,
+            no_newline(&self.new, inserted),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shortcuts::*;
+
+    fn raw(lines: &[RichString]) -> Vec<&str> {
+        lines.iter().map(RichString::raw).collect()
+    }
+
+    #[test]
+    fn test_edit_script() {
+        let ops = edit_script(
+            &['a', 'b', 'c', 'a', 'b', 'b', 'a'],
+            &['c', 'b', 'a', 'b', 'a', 'c'],
+        );
+        assert_eq!(
+            ops.iter().filter(|op| !matches!(op, Op::Equal(..))).count(),
+            5
+        );
+        assert_eq!(edit_script::<char>(&[], &[]), vec![]);
+        assert_eq!(edit_script(&['a'], &[]), vec![Op::Delete(0)]);
+        assert_eq!(edit_script(&[], &['a']), vec![Op::Insert(0)]);
+        assert_eq!(
+            edit_script(&['a', 'b'], &['a', 'c']),
+            vec![Op::Equal(0, 0), Op::Delete(1), Op::Insert(1)]
+        );
+
+        // unrelated inputs need a script as long as both of them
+        let old: Vec<usize> = (0..3000).collect();
+        let new: Vec<usize> = (3000..6000).collect();
+        let ops = edit_script(&old, &new);
+        assert_eq!(ops.len(), 6000);
+        assert!(ops[..3000].iter().all(|op| matches!(op, Op::Delete(_))));
+
+        let old: Vec<char> = "the quick brown fox jumps".chars().collect();
+        let new: Vec<char> = "a quick brown dog jumped".chars().collect();
+        let ops = edit_script(&old, &new);
+        let equal = ops.iter().filter(|op| matches!(op, Op::Equal(..))).count();
+        assert_eq!(equal, 19);
+        assert_eq!(ops.len(), old.len() + new.len() - equal);
+    }
+
+    #[test]
+    fn test_tokens() {
+        assert_eq!(
+            tokens("let x = foo(1, 2);"),
+            vec!["let", " ", "x", " ", "=", " ", "foo", "(", "1", ",", " ", "2", ")", ";"]
+        );
+        assert!(tokens("").is_empty());
+    }
+
+    #[test]
+    fn test_unified() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n";
+        let new = "a\nb\nc\nd\ne\nf\ng\nh\nI\nj\nk\n";
+
+        assert_eq!(
+            raw(&Diff::new(old, new).context(1).unified()),
+            vec!["@@ -8,3 +8,4 @@", " h", "-i", "+I", " j", "+k"]
+        );
+        assert_eq!(
+            raw(&Diff::new("a\nb\nc\nd\ne\nf\ng\n", "A\nb\nc\nd\ne\nf\nG\n")
+                .context(2)
+                .unified()),
+            vec![
+                "@@ -1,3 +1,3 @@",
+                "-a",
+                "+A",
+                " b",
+                " c",
+                "@@ -5,3 +5,3 @@",
+                " e",
+                " f",
+                "-g",
+                "+G",
+            ]
+        );
+        assert_eq!(
+            raw(&Diff::new("a\nb\n", "b\n").unified()),
+            vec!["@@ -1,2 +1,1 @@", "-a", " b"]
+        );
+        assert_eq!(
+            raw(&Diff::new("", "a\n").unified()),
+            vec!["@@ -0,0 +1,1 @@", "+a"]
+        );
+        assert!(Diff::new("a\nb\n", "a\nb\n").is_empty());
+        assert!(Diff::new("a\nb\n", "a\nb\n").unified().is_empty());
+    }
+
+    #[test]
+    fn test_line_breaks() {
+        let diff = Diff::new("a\nb", "a\nb\n");
+        assert!(!diff.is_empty());
+        assert_eq!(
+            raw(&diff.unified()),
+            vec![
+                "@@ -1,2 +1,2 @@",
+                " a",
+                "-b",
+                "\\ No newline at end of file",
+                "+b",
+            ]
+        );
+
+        assert_eq!(
+            raw(&Diff::new("a\nb", "a\nc").unified()),
+            vec![
+                "@@ -1,2 +1,2 @@",
+                " a",
+                "-b",
+                "\\ No newline at end of file",
+                "+c",
+                "\\ No newline at end of file",
+            ]
+        );
+        assert_eq!(
+            raw(&Diff::new("a\nb", "b").unified()),
+            vec![
+                "@@ -1,2 +1,1 @@",
+                "-a",
+                " b",
+                "\\ No newline at end of file",
+            ]
+        );
+
+        let diff = Diff::new("a\r\nb\n", "a\nb\n");
+        assert!(!diff.is_empty());
+        assert_eq!(
+            raw(&diff.unified()),
+            vec!["@@ -1,2 +1,2 @@", "-a^M", "+a", " b"]
+        );
+    }
+
+    #[test]
+    fn test_word_diff() {
+        let theme = Diff::default_theme();
+        let lines = Diff::new("let x = 1;\n", "let y = 1;\n").unified();
+
+        assert_eq!(
+            raw(&lines),
+            vec!["@@ -1,1 +1,1 @@", "-let x = 1;", "+let y = 1;"]
+        );
+        assert_eq!(lines[0].style_at(0), theme.get("diff.hunk"));
+        assert_eq!(lines[1].style_at(0), RED);
+        assert_eq!(lines[1].style_at(1), RED);
+        assert_eq!(lines[1].style_at(5), theme.get("diff.delete.word"));
+        assert_eq!(lines[2].style_at(5), GREEN & theme.get("diff.insert.word"));
+        assert_eq!(lines[2].style_at(6), GREEN);
+    }
+
+    #[test]
+    fn test_side_by_side() {
+        let lines = Diff::new("same\nold line\ngone", "same\nnew line").side_by_side(25);
+
+        assert_eq!(
+            raw(&lines),
+            vec![
+                "@@ -1,3 +1,2 @@",
+                " same       │  same      ",
+                "-old line   │ +new line  ",
+                "-gone       │            ",
+            ]
+        );
+
+        let lines = Diff::new("a very long line", "short").side_by_side(15);
+        assert_eq!(raw(&lines)[1], "-a ve… │ +short");
+    }
+}
@@ -0,0 +1,467 @@
+use crate::error::ParseStyleError;
+use crate::{RichString, Style};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// Kinds of files that `ls` colors differently
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FileType {
+    /// Any text that is not a file name
+    NORMAL,
+    /// Regular file
+    FILE,
+    /// Directory
+    DIRECTORY,
+    /// Symbolic link
+    SYMLINK,
+    /// Regular file with more than one hard link
+    MULTIHARDLINK,
+    /// Named pipe
+    PIPE,
+    /// Socket
+    SOCKET,
+    /// Door (Solaris)
+    DOOR,
+    /// Block device
+    BLOCKDEVICE,
+    /// Character device
+    CHARDEVICE,
+    /// Symbolic link pointing to a file that doesn't exist
+    ORPHAN,
+    /// File that doesn't exist, but is pointed to by a symbolic link
+    MISSING,
+    /// File with the set-user-ID bit
+    SETUID,
+    /// File with the set-group-ID bit
+    SETGID,
+    /// File with capabilities
+    CAPABILITY,
+    /// Directory that is sticky and writable by others
+    STICKYOTHERWRITABLE,
+    /// Directory that is writable by others
+    OTHERWRITABLE,
+    /// Directory with the sticky bit
+    STICKY,
+    /// Executable file
+    EXECUTABLE,
+}
+
+impl FileType {
+    /// Returns the file type for a two-letter `LS_COLORS` key
+    fn from_key(key: &str) -> Option<Self> {
+        Some(match key {
+            "no" => Self::NORMAL,
+            "fi" => Self::FILE,
+            "di" => Self::DIRECTORY,
+            "ln" => Self::SYMLINK,
+            "mh" => Self::MULTIHARDLINK,
+            "pi" => Self::PIPE,
+            "so" => Self::SOCKET,
+            "do" => Self::DOOR,
+            "bd" => Self::BLOCKDEVICE,
+            "cd" => Self::CHARDEVICE,
+            "or" => Self::ORPHAN,
+            "mi" => Self::MISSING,
+            "su" => Self::SETUID,
+            "sg" => Self::SETGID,
+            "ca" => Self::CAPABILITY,
+            "tw" => Self::STICKYOTHERWRITABLE,
+            "ow" => Self::OTHERWRITABLE,
+            "st" => Self::STICKY,
+            "ex" => Self::EXECUTABLE,
+            _ => return None,
+        })
+    }
+
+    /// Returns the file type for a keyword of the `dircolors` database
+    fn from_keyword(keyword: &str) -> Option<Self> {
+        Some(match keyword {
+            "NORMAL" | "NORM" => Self::NORMAL,
+            "FILE" => Self::FILE,
+            "DIR" => Self::DIRECTORY,
+            "LNK" | "LINK" | "SYMLINK" => Self::SYMLINK,
+            "MULTIHARDLINK" => Self::MULTIHARDLINK,
+            "FIFO" | "PIPE" => Self::PIPE,
+            "SOCK" => Self::SOCKET,
+            "DOOR" => Self::DOOR,
+            "BLK" | "BLOCK" => Self::BLOCKDEVICE,
+            "CHR" | "CHAR" => Self::CHARDEVICE,
+            "ORPHAN" => Self::ORPHAN,
+            "MISSING" => Self::MISSING,
+            "SETUID" => Self::SETUID,
+            "SETGID" => Self::SETGID,
+            "CAPABILITY" => Self::CAPABILITY,
+            "STICKY_OTHER_WRITABLE" => Self::STICKYOTHERWRITABLE,
+            "OTHER_WRITABLE" => Self::OTHERWRITABLE,
+            "STICKY" => Self::STICKY,
+            "EXEC" => Self::EXECUTABLE,
+            _ => return None,
+        })
+    }
+
+    /// Returns the file type to use when this one has no style
+    fn fallback(self) -> Option<Self> {
+        match self {
+            Self::NORMAL => None,
+            Self::FILE => Some(Self::NORMAL),
+            Self::ORPHAN => Some(Self::SYMLINK),
+            Self::MISSING => Some(Self::ORPHAN),
+            Self::SETUID | Self::SETGID | Self::CAPABILITY => Some(Self::EXECUTABLE),
+            Self::STICKYOTHERWRITABLE | Self::OTHERWRITABLE | Self::STICKY => Some(Self::DIRECTORY),
+            _ => Some(Self::FILE),
+        }
+    }
+}
+
+/// File colors, as configured with `LS_COLORS` or a `dircolors` database
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LsColors {
+    types: HashMap<FileType, Style>,
+    /// Suffixes of file names, in lowercase
+    suffixes: Vec<(String, Style)>,
+    /// Whether symbolic links take the style of their target
+    link_target: bool,
+}
+
+impl LsColors {
+    /// Creates a new configuration without any color
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses the value of the `LS_COLORS` environment variable
+    ///
+    /// It is a list of `key=codes` entries separated by colons, where keys
+    /// are either two-letter file types like `di` or patterns like `*.tar`,
+    /// and codes are SGR codes like `01;34`. Unknown keys are ignored.
+    pub fn parse(value: &str) -> Result<Self, ParseStyleError> {
+        let mut colors = Self::new();
+
+        for entry in value.split(':').filter(|entry| !entry.is_empty()) {
+            let (key, codes) = entry
+                .split_once('=')
+                .ok_or_else(|| ParseStyleError::new(entry))?;
+
+            if let Some(pattern) = key.strip_prefix('*') {
+                colors.set_suffix(pattern, Style::from_sgr(codes)?);
+            } else if let Some(file_type) = FileType::from_key(key) {
+                colors.set_type(file_type, codes)?;
+            }
+        }
+
+        Ok(colors)
+    }
+
+    /// Reads the `LS_COLORS` environment variable
+    ///
+    /// Returns an empty configuration if the variable is not set.
+    pub fn from_env() -> Result<Self, ParseStyleError> {
+        match env::var("LS_COLORS") {
+            Ok(value) => Self::parse(&value),
+            Err(_) => Ok(Self::new()),
+        }
+    }
+
+    /// Parses a database in the format of the `dircolors` program
+    ///
+    /// Each line is a keyword like `DIR`, or a pattern like `*.tar` or
+    /// `.tar`, followed by SGR codes. Terminal-related lines like `TERM` and
+    /// unknown keywords are ignored, as are comments starting with `#`.
+    pub fn parse_dircolors(source: &str) -> Result<Self, ParseStyleError> {
+        let mut colors = Self::new();
+
+        for line in source.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let mut words = line.split_whitespace();
+            let keyword = words.next().unwrap_or_default();
+            let codes = words.next().ok_or_else(|| ParseStyleError::new(line))?;
+
+            if let Some(pattern) = keyword.strip_prefix('*') {
+                colors.set_suffix(pattern, Style::from_sgr(codes)?);
+            } else if keyword.starts_with('.') {
+                colors.set_suffix(keyword, Style::from_sgr(codes)?);
+            } else if let Some(file_type) = FileType::from_keyword(keyword) {
+                colors.set_type(file_type, codes)?;
+            }
+        }
+
+        Ok(colors)
+    }
+
+    fn set_type(&mut self, file_type: FileType, codes: &str) -> Result<(), ParseStyleError> {
+        if file_type == FileType::SYMLINK && codes == "target" {
+            self.link_target = true;
+        } else {
+            self.types.insert(file_type, Style::from_sgr(codes)?);
+        }
+        Ok(())
+    }
+
+    fn set_suffix(&mut self, suffix: &str, style: Style) {
+        let suffix = suffix.to_lowercase();
+        self.suffixes.retain(|(existing, _)| *existing != suffix);
+        self.suffixes.push((suffix, style));
+    }
+
+    /// Returns the style for a kind of file
+    ///
+    /// If that kind of file has no style, the style of a more general kind is used.
+    pub fn style_for_type(&self, file_type: FileType) -> Style {
+        let mut current = Some(file_type);
+
+        while let Some(file_type) = current {
+            if let Some(style) = self.types.get(&file_type) {
+                return *style;
+            }
+            current = file_type.fallback();
+        }
+
+        Style::plain()
+    }
+
+    /// Returns the style for the name of a regular file, based on its suffix
+    ///
+    /// The longest matching suffix is used, regardless of case.
+    pub fn style_for_name(&self, name: &str) -> Option<Style> {
+        let name = name.to_lowercase();
+
+        self.suffixes
+            .iter()
+            .filter(|(suffix, _)| name.ends_with(suffix.as_str()))
+            .max_by_key(|(suffix, _)| suffix.len())
+            .map(|(_, style)| *style)
+    }
+
+    /// Returns the style for a file on the disk, the same way `ls` would
+    pub fn style_for_path<P: AsRef<Path>>(&self, path: P) -> Style {
+        let path = path.as_ref();
+        let file_type = file_type(path, |file_type| self.is_colored(file_type));
+
+        if file_type == FileType::SYMLINK && self.link_target {
+            return self.style_for_path(fs::canonicalize(path).unwrap_or_default());
+        }
+
+        let by_name = path
+            .file_name()
+            .and_then(|name| self.style_for_name(&name.to_string_lossy()));
+
+        match (file_type, by_name) {
+            (FileType::FILE, Some(style)) => style,
+            _ => self.style_for_type(file_type),
+        }
+    }
+
+    /// Returns `true` if a kind of file has a style of its own
+    fn is_colored(&self, file_type: FileType) -> bool {
+        self.types
+            .get(&file_type)
+            .is_some_and(|style| *style != Style::plain())
+    }
+
+    /// Styles a path the same way `ls` would
+    pub fn style_path<P: AsRef<Path>>(&self, path: P) -> RichString {
+        let path = path.as_ref();
+        RichString::from(&path.to_string_lossy(), self.style_for_path(path))
+    }
+}
+
+/// Finds out the kind of a file on the disk
+///
+/// Like `ls`, regular files are only given a more specific kind, like
+/// [FileType::EXECUTABLE], if that kind is colored.
+fn file_type<F: Fn(FileType) -> bool>(path: &Path, is_colored: F) -> FileType {
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(_) => return FileType::MISSING,
+    };
+    let kind = metadata.file_type();
+
+    if kind.is_symlink() {
+        return if fs::metadata(path).is_ok() {
+            FileType::SYMLINK
+        } else {
+            FileType::ORPHAN
+        };
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{FileTypeExt, MetadataExt};
+
+        let mode = metadata.mode();
+        let sticky = mode & 0o1000 != 0;
+        let other_writable = mode & 0o002 != 0;
+
+        if kind.is_dir() {
+            return match (sticky, other_writable) {
+                (true, true) => FileType::STICKYOTHERWRITABLE,
+                (false, true) => FileType::OTHERWRITABLE,
+                (true, false) => FileType::STICKY,
+                (false, false) => FileType::DIRECTORY,
+            };
+        } else if kind.is_fifo() {
+            return FileType::PIPE;
+        } else if kind.is_socket() {
+            return FileType::SOCKET;
+        } else if kind.is_block_device() {
+            return FileType::BLOCKDEVICE;
+        } else if kind.is_char_device() {
+            return FileType::CHARDEVICE;
+        } else if mode & 0o4000 != 0 && is_colored(FileType::SETUID) {
+            return FileType::SETUID;
+        } else if mode & 0o2000 != 0 && is_colored(FileType::SETGID) {
+            return FileType::SETGID;
+        } else if mode & 0o111 != 0 && is_colored(FileType::EXECUTABLE) {
+            return FileType::EXECUTABLE;
+        } else if metadata.nlink() > 1 && is_colored(FileType::MULTIHARDLINK) {
+            return FileType::MULTIHARDLINK;
+        }
+    }
+
+    if kind.is_dir() {
+        FileType::DIRECTORY
+    } else {
+        FileType::FILE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shortcuts::*;
+    use crate::{Color, Feature};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_parse() {
+        let colors =
+            LsColors::parse("rs=0:di=01;34:ln=target:*.tar=01;31:*README=4:ex=38;5;208:lc=\\e[")
+                .unwrap();
+
+        assert_eq!(colors.style_for_type(FileType::DIRECTORY), BOLD & BLUE);
+        assert_eq!(
+            colors.style_for_type(FileType::SETUID),
+            Style {
+                foreground: Color::FIXED(208),
+                ..Style::plain()
+            }
+        );
+        assert_eq!(colors.style_for_type(FileType::FILE), Style::plain());
+        assert!(colors.link_target);
+        assert_eq!(colors.style_for_name("archive.tar"), Some(BOLD & RED));
+        assert_eq!(colors.style_for_name("README"), Some(UNDERLINE));
+        assert_eq!(colors.style_for_name("main.rs"), None);
+
+        assert!(LsColors::parse("di").is_err());
+        assert!(LsColors::parse("di=01;blue").is_err());
+    }
+
+    #[test]
+    fn test_parse_dircolors() {
+        let colors = LsColors::parse_dircolors(
+            "# Configuration file for dircolors\n\
+             TERM xterm*\n\
+             COLOR tty\n\
+             NORMAL 00\n\
+             DIR 01;34 # directory\n\
+             ORPHAN 05;31;40\n\
+             .tar 01;31\n\
+             *.TGZ 01;31\n",
+        )
+        .unwrap();
+
+        assert_eq!(colors.style_for_type(FileType::DIRECTORY), BOLD & BLUE);
+        assert_eq!(
+            colors.style_for_type(FileType::ORPHAN),
+            Style {
+                blink: Feature::ENABLED,
+                ..RED & ON_BLACK
+            }
+        );
+        assert_eq!(
+            colors.style_for_type(FileType::MISSING),
+            colors.style_for_type(FileType::ORPHAN)
+        );
+        assert_eq!(colors.style_for_name("a.tar"), Some(BOLD & RED));
+        assert_eq!(colors.style_for_name("a.tgz"), Some(BOLD & RED));
+
+        assert!(LsColors::parse_dircolors("DIR").is_err());
+    }
+
+    #[test]
+    fn test_style_for_name() {
+        let colors = LsColors::parse("*.gz=31:*.tar.gz=32").unwrap();
+
+        assert_eq!(colors.style_for_name("a.tar.gz"), Some(GREEN));
+        assert_eq!(colors.style_for_name("a.GZ"), Some(RED));
+    }
+
+    /// Creates an empty directory that no other test or process uses
+    fn unique_dir(name: &str) -> PathBuf {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos());
+        let dir = env::temp_dir().join(format!(
+            "perroquet-{}-{}-{}",
+            name,
+            std::process::id(),
+            nanos
+        ));
+        fs::create_dir(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_style_path() {
+        let colors = LsColors::parse("di=01;34:fi=33:mi=31:*.tar=01;31").unwrap();
+        let dir = unique_dir("style-path");
+        let archive = dir.join("archive.tar");
+        fs::write(&archive, "").unwrap();
+        let text = dir.join("notes.txt");
+        fs::write(&text, "").unwrap();
+
+        assert_eq!(colors.style_for_path(&dir), BOLD & BLUE);
+        assert_eq!(colors.style_for_path(&archive), BOLD & RED);
+        assert_eq!(colors.style_for_path(&text), YELLOW);
+        assert_eq!(colors.style_for_path(dir.join("missing.tar")), RED);
+        assert_eq!(
+            colors.style_path(&archive),
+            RichString::from(&archive.to_string_lossy(), BOLD & RED)
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_style_path_special_files() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = unique_dir("special-files");
+        let script = dir.join("run.sh");
+        fs::write(&script, "").unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        let archive = dir.join("archive.tar");
+        fs::write(&archive, "").unwrap();
+        fs::hard_link(&archive, dir.join("link.tar")).unwrap();
+
+        let colors = LsColors::parse("fi=33:*.sh=32:*.tar=31").unwrap();
+        assert_eq!(colors.style_for_path(&script), GREEN);
+        assert_eq!(colors.style_for_path(&archive), RED);
+
+        let colors = LsColors::parse("fi=33:ex=01;32:mh=34:*.sh=32:*.tar=31").unwrap();
+        assert_eq!(colors.style_for_path(&script), BOLD & GREEN);
+        assert_eq!(colors.style_for_path(&archive), BLUE);
+
+        let colors = LsColors::parse("ex=00:*.sh=32").unwrap();
+        assert_eq!(colors.style_for_path(&script), GREEN);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
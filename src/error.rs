@@ -0,0 +1,114 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// Error returned when an index or a range is not valid for a string
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum IndexError {
+    /// The index is past the end of the string
+    OutOfBounds {
+        /// The invalid index
+        index: usize,
+        /// The length of the string, in the same unit as the index
+        len: usize,
+    },
+    /// The byte index is inside a multi-byte character
+    NotCharBoundary(usize),
+    /// The start of the range is after its end
+    InvalidRange {
+        /// Start of the range
+        start: usize,
+        /// End of the range
+        end: usize,
+    },
+}
+
+impl fmt::Display for IndexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutOfBounds { index, len } => {
+                write!(f, "index {} is out of bounds (length is {})", index, len)
+            }
+            Self::NotCharBoundary(index) => {
+                write!(f, "byte index {} is not a character boundary", index)
+            }
+            Self::InvalidRange { start, end } => {
+                write!(f, "range starts at {} but ends at {}", start, end)
+            }
+        }
+    }
+}
+
+impl Error for IndexError {}
+
+/// Error returned when a style cannot be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseStyleError {
+    word: String,
+}
+
+impl ParseStyleError {
+    pub(crate) fn new(word: &str) -> Self {
+        Self {
+            word: String::from(word),
+        }
+    }
+
+    /// Returns the part of the input that was not recognised
+    pub fn word(&self) -> &str {
+        &self.word
+    }
+}
+
+impl fmt::Display for ParseStyleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown style attribute `{}`", self.word)
+    }
+}
+
+impl Error for ParseStyleError {}
+
+/// Error returned when a theme cannot be loaded
+#[derive(Debug)]
+pub enum ThemeError {
+    /// The file could not be read
+    Io(io::Error),
+    /// A line is neither a section header, an entry nor a comment
+    Syntax {
+        /// Line number, starting at 1
+        line: usize,
+    },
+    /// The style of an entry is not valid
+    Style {
+        /// Line number, starting at 1
+        line: usize,
+        /// What went wrong
+        error: ParseStyleError,
+    },
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "cannot read theme: {}", error),
+            Self::Syntax { line } => write!(f, "syntax error on line {}", line),
+            Self::Style { line, error } => write!(f, "{} on line {}", error, line),
+        }
+    }
+}
+
+impl Error for ThemeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Syntax { .. } => None,
+            Self::Style { error, .. } => Some(error),
+        }
+    }
+}
+
+impl From<io::Error> for ThemeError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
@@ -0,0 +1,228 @@
+use crate::rgb::{from_linear, to_linear};
+use crate::{Color, RichString, Style};
+
+/// Colors interpolated along a string
+///
+/// The stops are evenly spaced, and the colors between them are
+/// interpolated in the OKLab color space, which gives smoother transitions
+/// than plain RGB.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Gradient {
+    stops: Vec<Color>,
+    background: bool,
+}
+
+impl Gradient {
+    /// Creates a gradient between two colors
+    pub fn new(from: Color, to: Color) -> Self {
+        Self::with_stops([from, to])
+    }
+
+    /// Creates a gradient going through several colors
+    pub fn with_stops<I: IntoIterator<Item = Color>>(stops: I) -> Self {
+        Self {
+            stops: stops.into_iter().collect(),
+            background: false,
+        }
+    }
+
+    /// Applies the gradient to the background instead of the text color
+    pub fn on_background(mut self) -> Self {
+        self.background = true;
+        self
+    }
+
+    /// Returns the color at a position of the gradient, from 0 to 1
+    ///
+    /// Stops without an RGB value, like [Color::INHERIT], are not
+    /// interpolated: the nearest stop is used instead.
+    pub fn color_at(&self, t: f64) -> Color {
+        match self.stops.len() {
+            0 => Color::INHERIT,
+            1 => self.stops[0],
+            count => {
+                let position = t.clamp(0.0, 1.0) * (count - 1) as f64;
+                let index = (position.floor() as usize).min(count - 2);
+                interpolate(
+                    self.stops[index],
+                    self.stops[index + 1],
+                    position - index as f64,
+                )
+            }
+        }
+    }
+
+    /// Returns the style for a position of the gradient
+    fn style_at(&self, t: f64) -> Style {
+        let color = self.color_at(t);
+        if self.background {
+            Style {
+                background: color,
+                ..Style::plain()
+            }
+        } else {
+            Style {
+                foreground: color,
+                ..Style::plain()
+            }
+        }
+    }
+}
+
+impl RichString {
+    /// Colors the text with a gradient between two colors
+    ///
+    /// See [Self::apply_gradient()].
+    pub fn gradient(&mut self, from: Color, to: Color) {
+        self.apply_gradient(&Gradient::new(from, to));
+    }
+
+    /// See [Self::apply_gradient()].
+    pub fn into_gradient(mut self, gradient: &Gradient) -> Self {
+        self.apply_gradient(gradient);
+        self
+    }
+
+    /// Gives each character a color from a gradient
+    ///
+    /// The first character gets the start of the gradient and the last one
+    /// gets the end. Only characters that inherit their color are changed,
+    /// and the other properties of the style are kept.
+    pub fn apply_gradient(&mut self, gradient: &Gradient) {
+        let last = self.len().saturating_sub(1).max(1) as f64;
+        self.restyle_chars(|index, style| style & gradient.style_at(index as f64 / last));
+    }
+}
+
+/// Interpolates between two colors in the OKLab color space
+fn interpolate(from: Color, to: Color, t: f64) -> Color {
+    if t <= 0.0 {
+        return from;
+    } else if t >= 1.0 {
+        return to;
+    }
+
+    match (from.to_rgb(), to.to_rgb()) {
+        (Some(a), Some(b)) => {
+            let a = to_oklab(a);
+            let b = to_oklab(b);
+            from_oklab([
+                a[0] + (b[0] - a[0]) * t,
+                a[1] + (b[1] - a[1]) * t,
+                a[2] + (b[2] - a[2]) * t,
+            ])
+        }
+        _ if t < 0.5 => from,
+        _ => to,
+    }
+}
+
+/// Converts an RGB color to OKLab
+fn to_oklab((r, g, b): (u8, u8, u8)) -> [f64; 3] {
+    let (r, g, b) = (to_linear(r), to_linear(g), to_linear(b));
+
+    let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+    let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+    let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+
+    [
+        0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+        1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+        0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+    ]
+}
+
+/// Converts an OKLab color back to RGB
+fn from_oklab([lightness, a, b]: [f64; 3]) -> Color {
+    let l = (lightness + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+    let m = (lightness - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+    let s = (lightness - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+
+    Color::RGB(
+        from_linear(4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s),
+        from_linear(-1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s),
+        from_linear(-0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shortcuts::*;
+
+    #[test]
+    fn test_oklab() {
+        for color in [(0, 0, 0), (255, 255, 255), (255, 135, 0), (18, 52, 86)] {
+            assert_eq!(
+                from_oklab(to_oklab(color)),
+                Color::RGB(color.0, color.1, color.2)
+            );
+        }
+    }
+
+    #[test]
+    fn test_color_at() {
+        let gradient = Gradient::new(Color::RED, Color::RGB(0, 0, 255));
+        assert_eq!(gradient.color_at(0.0), Color::RED);
+        assert_eq!(gradient.color_at(-1.0), Color::RED);
+        assert_eq!(gradient.color_at(1.0), Color::RGB(0, 0, 255));
+
+        let gray = Gradient::new(Color::RGB(0, 0, 0), Color::RGB(255, 255, 255));
+        // OKLab lightness is halfway, which is darker than the RGB average
+        assert_eq!(gray.color_at(0.5), Color::RGB(99, 99, 99));
+
+        let stops = Gradient::with_stops([Color::RED, Color::GREEN, Color::BLUE]);
+        assert_eq!(stops.color_at(0.5), Color::GREEN);
+        assert_eq!(stops.color_at(1.0), Color::BLUE);
+
+        let inherit = Gradient::new(Color::RED, Color::INHERIT);
+        assert_eq!(inherit.color_at(0.25), Color::RED);
+        assert_eq!(inherit.color_at(0.75), Color::INHERIT);
+
+        assert_eq!(Gradient::with_stops([]).color_at(0.5), Color::INHERIT);
+        assert_eq!(
+            Gradient::with_stops([Color::CYAN]).color_at(0.5),
+            Color::CYAN
+        );
+    }
+
+    #[test]
+    fn test_gradient() {
+        let mut string = RichString::from("abc", BOLD);
+        string.gradient(Color::RED, Color::BLUE);
+
+        assert_eq!(string.style_at(0), BOLD & RED);
+        assert_eq!(string.style_at(1).bold, BOLD.bold);
+        assert!(matches!(string.style_at(1).foreground, Color::RGB(..)));
+        assert_eq!(string.style_at(2), BOLD & BLUE);
+
+        let mut single = RichString::from("a", Style::plain());
+        single.gradient(Color::RED, Color::BLUE);
+        assert_eq!(single.style_at(0), RED);
+    }
+
+    #[test]
+    fn test_apply_gradient() {
+        let string = (RichString::from("ab", Style::plain()) + RichString::from("cd", ITALIC))
+            .into_gradient(
+                &Gradient::with_stops([Color::RED, Color::GREEN, Color::RED, Color::GREEN])
+                    .on_background(),
+            );
+
+        assert_eq!(string.style_at(0), ON_RED);
+        assert_eq!(string.style_at(1), ON_GREEN);
+        assert_eq!(string.style_at(2), ON_RED & ITALIC);
+        assert_eq!(string.style_at(3), ON_GREEN & ITALIC);
+
+        let colored = (RichString::from("ab", YELLOW) + RichString::from("cd", BOLD))
+            .into_gradient(&Gradient::new(Color::RED, Color::GREEN));
+        assert_eq!(colored.style_at(0), YELLOW);
+        assert_eq!(colored.style_at(1), YELLOW);
+        assert_eq!(colored.style_at(3), GREEN & BOLD);
+
+        let named =
+            RichString::named("ab", "title").into_gradient(&Gradient::new(Color::RED, Color::BLUE));
+        assert_eq!(named.name_at(1), Some("title"));
+        assert_eq!(named.spans().count(), 2);
+    }
+}
@@ -0,0 +1,133 @@
+use crate::{RichString, Style, Theme};
+use std::ops::Range;
+
+/// Kinds of tokens found by a [Highlighter]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TokenKind {
+    /// Comments
+    COMMENT,
+    /// Reserved words of the language
+    KEYWORD,
+    /// Literals like `true` or `null`
+    CONSTANT,
+    /// Numbers
+    NUMBER,
+    /// Strings
+    STRING,
+    /// Names of properties, like the keys of a JSON object
+    KEY,
+    /// Variables
+    VARIABLE,
+    /// Names of functions, or commands in a shell
+    FUNCTION,
+    /// Operators
+    OPERATOR,
+    /// Brackets, commas and other punctuation
+    PUNCTUATION,
+}
+
+impl TokenKind {
+    /// Returns the name of the style of this kind in a theme, like
+    /// `highlight.string`
+    pub fn style_name(self) -> &'static str {
+        match self {
+            Self::COMMENT => "highlight.comment",
+            Self::KEYWORD => "highlight.keyword",
+            Self::CONSTANT => "highlight.constant",
+            Self::NUMBER => "highlight.number",
+            Self::STRING => "highlight.string",
+            Self::KEY => "highlight.key",
+            Self::VARIABLE => "highlight.variable",
+            Self::FUNCTION => "highlight.function",
+            Self::OPERATOR => "highlight.operator",
+            Self::PUNCTUATION => "highlight.punctuation",
+        }
+    }
+
+    /// Returns the default theme for highlighted code
+    pub fn default_theme() -> Theme {
+        let style = |style: &str| style.parse::<Style>().unwrap_or_default();
+
+        Theme::new()
+            .with("highlight.comment", style("darkgrey italic"))
+            .with("highlight.keyword", style("purple bold"))
+            .with("highlight.constant", style("cyan"))
+            .with("highlight.number", style("cyan"))
+            .with("highlight.string", style("green"))
+            .with("highlight.key", style("blue"))
+            .with("highlight.variable", style("yellow"))
+            .with("highlight.function", style("blue bold"))
+            .with("highlight.operator", style("purple"))
+    }
+}
+
+/// Splits source code into tokens to give them styles
+pub trait Highlighter {
+    /// Returns the tokens of the source, as byte ranges in order
+    ///
+    /// Parts of the source that are not in any token stay plain.
+    fn tokens(&self, source: &str) -> Vec<(Range<usize>, TokenKind)>;
+
+    /// Highlights the source with the default theme
+    fn highlight(&self, source: &str) -> RichString {
+        self.highlight_with(source, &TokenKind::default_theme())
+    }
+
+    /// Highlights the source with the styles of a theme
+    ///
+    /// The names of the styles are given by [TokenKind::style_name()].
+    fn highlight_with(&self, source: &str, theme: &Theme) -> RichString {
+        let mut highlighted = RichString::new();
+        let mut position = 0;
+
+        for (range, kind) in self.tokens(source) {
+            let valid = position <= range.start
+                && range.start < range.end
+                && source.get(range.clone()).is_some();
+            if !valid {
+                continue;
+            }
+
+            highlighted.push_plain(&source[position..range.start]);
+            highlighted.push(&RichString::named(
+                &source[range.clone()],
+                kind.style_name(),
+            ));
+            position = range.end;
+        }
+        highlighted.push_plain(&source[position..]);
+
+        highlighted.into_resolved(theme)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shortcuts::*;
+
+    struct Words;
+
+    impl Highlighter for Words {
+        fn tokens(&self, _: &str) -> Vec<(Range<usize>, TokenKind)> {
+            vec![
+                (0..3, TokenKind::KEYWORD),
+                (2..5, TokenKind::STRING),
+                (4..6, TokenKind::STRING),
+                (8..100, TokenKind::NUMBER),
+            ]
+        }
+    }
+
+    #[test]
+    fn test_highlight() {
+        let theme = Theme::new()
+            .with("highlight.keyword", BOLD)
+            .with("highlight", RED);
+
+        assert_eq!(
+            Words.highlight_with("let é = 1", &theme),
+            RichString::from("let", BOLD & RED) + " " + RichString::from("é", RED) + " = 1"
+        );
+    }
+}
@@ -0,0 +1,156 @@
+/// Number of characters between two checkpoints
+const STRIDE: usize = 64;
+
+/// Maps character offsets to byte offsets in a string
+///
+/// The byte offsets of some characters are stored as checkpoints, at most
+/// `2 * STRIDE` characters apart, so that converting an offset only needs a
+/// binary search followed by a short walk through the text.
+#[derive(Debug, Clone, Default)]
+pub struct CharIndex {
+    /// Sorted `(char, byte)` offsets, always starting with `(0, 0)` when not empty
+    checkpoints: Vec<(usize, usize)>,
+    chars: usize,
+    bytes: usize,
+}
+
+impl CharIndex {
+    /// Indexes a whole string
+    pub fn new(text: &str) -> Self {
+        let mut index = Self::default();
+        index.edit(text, 0, 0);
+        index
+    }
+
+    /// Returns the number of characters in the indexed string
+    pub fn len(&self) -> usize {
+        self.chars
+    }
+
+    /// Returns the byte offset of a character
+    ///
+    /// Offsets past the end of the string are mapped to the length of the string.
+    pub fn byte(&self, text: &str, char: usize) -> usize {
+        if char >= self.chars {
+            return self.bytes;
+        }
+
+        let (c, b) = self.checkpoints[self.checkpoints.partition_point(|&(c, _)| c <= char) - 1];
+        text[b..]
+            .char_indices()
+            .nth(char - c)
+            .map_or(self.bytes, |(offset, _)| b + offset)
+    }
+
+    /// Returns the character offset of a byte
+    ///
+    /// The byte offset must lie on a character boundary.
+    pub fn char(&self, text: &str, byte: usize) -> usize {
+        if byte >= self.bytes {
+            return self.chars;
+        }
+
+        let (c, b) = self.checkpoints[self.checkpoints.partition_point(|&(_, b)| b <= byte) - 1];
+        c + text[b..byte].chars().count()
+    }
+
+    /// Updates the index after the text was edited
+    ///
+    /// `text` is the new text, in which `removed` characters starting at
+    /// `start` have been replaced with any number of new characters.
+    ///
+    /// Only the edited region is re-indexed, but the checkpoints after it
+    /// are shifted, which is linear in their number (`len() / STRIDE`).
+    pub fn edit(&mut self, text: &str, start: usize, removed: usize) {
+        // the last checkpoint before the edit is still valid
+        let lo = self
+            .checkpoints
+            .partition_point(|&(c, _)| c <= start)
+            .saturating_sub(1);
+        let (from_char, from_byte) = self.checkpoints.get(lo).copied().unwrap_or((0, 0));
+
+        // the checkpoints after the edit only need to be moved
+        let hi = self
+            .checkpoints
+            .partition_point(|&(c, _)| c < start + removed)
+            .max(lo + 1)
+            .min(self.checkpoints.len());
+        let (to_char, to_byte) = self
+            .checkpoints
+            .get(hi)
+            .copied()
+            .unwrap_or((self.chars, self.bytes));
+
+        let byte_delta = text.len() as isize - self.bytes as isize;
+        let to_byte = (to_byte as isize + byte_delta) as usize;
+
+        // re-index everything in between
+        let mut region = Vec::new();
+        let mut count = 0;
+        for (offset, _) in text[from_byte..to_byte].char_indices() {
+            if count % STRIDE == 0 {
+                region.push((from_char + count, from_byte + offset));
+            }
+            count += 1;
+        }
+        if hi < self.checkpoints.len() && region.len() > 1 && count % STRIDE != 0 {
+            // merge the last gap instead of leaving a small one
+            region.pop();
+        }
+        let char_delta = count as isize - (to_char - from_char) as isize;
+
+        let moved = lo + region.len();
+        self.checkpoints.splice(lo..hi, region);
+        for (c, b) in self.checkpoints[moved..].iter_mut() {
+            *c = (*c as isize + char_delta) as usize;
+            *b = (*b as isize + byte_delta) as usize;
+        }
+
+        self.chars = (self.chars as isize + char_delta) as usize;
+        self.bytes = text.len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(index: &CharIndex, text: &str) {
+        assert_eq!(index.len(), text.chars().count());
+        for (c, (b, _)) in text.char_indices().enumerate() {
+            assert_eq!(index.byte(text, c), b);
+            assert_eq!(index.char(text, b), c);
+        }
+        assert_eq!(index.byte(text, index.len()), text.len());
+        for pair in index.checkpoints.windows(2) {
+            assert!(pair[1].0 - pair[0].0 < 2 * STRIDE);
+        }
+    }
+
+    #[test]
+    fn test_new() {
+        let text = "çōłöŕfûl ".repeat(50);
+        check(&CharIndex::new(&text), &text);
+        check(&CharIndex::new(""), "");
+    }
+
+    #[test]
+    fn test_edit() {
+        let mut text = String::new();
+        let mut index = CharIndex::new(&text);
+
+        for i in 0..300 {
+            let at = (i * 7) % (index.len() + 1);
+            let byte = index.byte(&text, at);
+            text.insert_str(byte, if i % 2 == 0 { "é" } else { "ab" });
+            index.edit(&text, at, 0);
+            check(&index, &text);
+        }
+
+        let byte = index.byte(&text, 10);
+        let end = index.byte(&text, 250);
+        text.replace_range(byte..end, "ü");
+        index.edit(&text, 10, 240);
+        check(&index, &text);
+    }
+}
@@ -0,0 +1,195 @@
+use crate::error::ParseStyleError;
+use crate::{Color, Decoration, Feature, Style, Theme};
+use std::env;
+
+impl Style {
+    /// Parses a color in the syntax of git's configuration, like `bold red ul`
+    ///
+    /// The first color is the text color and the second one is the
+    /// background color, `normal` leaving it unchanged. Colors are names
+    /// like `red` or `brightred`, palette indices from 0 to 255, or
+    /// hexadecimal RGB colors like `#ff0000`. Attributes are `bold`, `dim`,
+    /// `italic`, `ul`, `blink`, `reverse` and `strike`, optionally prefixed
+    /// with `no` or `no-` to turn them off.
+    pub fn from_git(value: &str) -> Result<Self, ParseStyleError> {
+        let mut style = Self::plain();
+        let mut colors = 0;
+
+        for word in value.split_whitespace() {
+            let lowercase = word.to_lowercase();
+
+            if let Some(color) = git_color(&lowercase) {
+                match colors {
+                    0 => style.foreground = color.unwrap_or(style.foreground),
+                    1 => style.background = color.unwrap_or(style.background),
+                    _ => return Err(ParseStyleError::new(word)),
+                }
+                colors += 1;
+            } else if lowercase == "reset" {
+                style = Self::plain();
+            } else {
+                let (attribute, enabled) = match lowercase.strip_prefix("no") {
+                    Some(attribute) => (attribute.trim_start_matches('-'), false),
+                    None => (lowercase.as_str(), true),
+                };
+                let feature = if enabled {
+                    Feature::ENABLED
+                } else {
+                    Feature::DISABLED
+                };
+
+                match attribute {
+                    "bold" => style.bold = feature,
+                    "dim" => style.dim = feature,
+                    "italic" => style.italic = feature,
+                    "blink" => style.blink = feature,
+                    "reverse" => style.reverse = feature,
+                    "ul" | "underline" if enabled => style.decoration = Decoration::UNDERLINE,
+                    "strike" if enabled => style.decoration = Decoration::LINETHROUGH,
+                    "ul" | "underline" | "strike" => style.decoration = Decoration::NONE,
+                    _ => return Err(ParseStyleError::new(word)),
+                }
+            }
+        }
+
+        Ok(style)
+    }
+}
+
+/// Parses a git color
+///
+/// `normal` is a valid color, which doesn't change anything.
+fn git_color(word: &str) -> Option<Option<Color>> {
+    let (name, bright) = match word.strip_prefix("bright") {
+        Some(name) => (name, true),
+        None => (word, false),
+    };
+
+    let index = match name {
+        "normal" | "-1" if !bright => return Some(None),
+        "default" if !bright => return Some(Some(Color::INHERIT)),
+        "black" => 0,
+        "red" => 1,
+        "green" => 2,
+        "yellow" => 3,
+        "blue" => 4,
+        "magenta" => 5,
+        "cyan" => 6,
+        "white" => 7,
+        _ if bright => return None,
+        _ => {
+            return match word.strip_prefix('#') {
+                Some(hex) if hex.len() == 3 => {
+                    let doubled: String = hex.chars().flat_map(|c| [c, c]).collect();
+                    format!("#{}", doubled).parse().ok().map(Some)
+                }
+                Some(_) => word.parse().ok().map(Some),
+                None => word.parse().ok().map(|index| Some(Color::palette(index))),
+            };
+        }
+    };
+
+    Some(Some(Color::palette(if bright { index + 8 } else { index })))
+}
+
+impl Theme {
+    /// Parses the value of the `GCC_COLORS` environment variable
+    ///
+    /// It is a list of `name=codes` entries separated by colons, like
+    /// `error=01;31:warning=01;35`, where codes are SGR codes. Each entry
+    /// becomes a style of the theme.
+    pub fn from_gcc_colors(value: &str) -> Result<Self, ParseStyleError> {
+        let mut theme = Self::new();
+
+        for entry in value.split(':').filter(|entry| !entry.is_empty()) {
+            let (name, codes) = entry
+                .split_once('=')
+                .ok_or_else(|| ParseStyleError::new(entry))?;
+            theme.set(name, Style::from_sgr(codes)?);
+        }
+
+        Ok(theme)
+    }
+
+    /// Reads the `GCC_COLORS` environment variable
+    ///
+    /// Returns `None` if the variable is not set.
+    pub fn from_gcc_colors_env() -> Option<Result<Self, ParseStyleError>> {
+        env::var("GCC_COLORS")
+            .ok()
+            .map(|value| Self::from_gcc_colors(&value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shortcuts::*;
+
+    #[test]
+    fn test_from_git() {
+        assert_eq!(Style::from_git(""), Ok(Style::plain()));
+        assert_eq!(Style::from_git("bold red ul"), Ok(BOLD & RED & UNDERLINE));
+        assert_eq!(
+            Style::from_git("#ff0000 black"),
+            Ok(Style {
+                foreground: Color::RGB(255, 0, 0),
+                ..ON_BLACK
+            })
+        );
+        assert_eq!(
+            Style::from_git("reverse"),
+            Ok(Style {
+                reverse: Feature::ENABLED,
+                ..Style::plain()
+            })
+        );
+        assert_eq!(Style::from_git("normal blue"), Ok(ON_BLUE));
+        assert_eq!(
+            Style::from_git("brightred 208 no-bold noitalic nostrike"),
+            Ok(Style {
+                foreground: Color::LIGHTRED,
+                background: Color::FIXED(208),
+                bold: Feature::DISABLED,
+                italic: Feature::DISABLED,
+                decoration: Decoration::NONE,
+                ..Style::plain()
+            })
+        );
+        assert_eq!(
+            Style::from_git("Yellow #0f8"),
+            Ok(Style {
+                background: Color::RGB(0, 255, 136),
+                ..YELLOW
+            })
+        );
+        assert_eq!(
+            Style::from_git("red blue green"),
+            Err(ParseStyleError::new("green"))
+        );
+        assert_eq!(
+            Style::from_git("bold sparkly"),
+            Err(ParseStyleError::new("sparkly"))
+        );
+        assert_eq!(
+            Style::from_git("brightnormal"),
+            Err(ParseStyleError::new("brightnormal"))
+        );
+    }
+
+    #[test]
+    fn test_from_gcc_colors() {
+        let theme = Theme::from_gcc_colors(
+            "error=01;31:warning=01;35:note=01;36:caret=01;32:locus=01:quote=01",
+        )
+        .unwrap();
+
+        assert_eq!(theme.get("error"), BOLD & RED);
+        assert_eq!(theme.get("warning"), BOLD & PURPLE);
+        assert_eq!(theme.get("locus"), BOLD);
+        assert_eq!(theme.get("fixit-insert"), Style::plain());
+
+        assert!(Theme::from_gcc_colors("error").is_err());
+        assert!(Theme::from_gcc_colors("error=red").is_err());
+    }
+}
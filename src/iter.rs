@@ -0,0 +1,92 @@
+use crate::style::{Style, StyleSpan};
+use std::str::Chars;
+
+/// Iterator over the styled runs of a [RichString](crate::RichString)
+///
+/// Created by [RichString::spans()](crate::RichString::spans) and
+/// [RichStr::spans()](crate::RichStr::spans).
+#[derive(Debug, Clone)]
+pub struct Spans<'a> {
+    text: &'a str,
+    spans: std::slice::Iter<'a, StyleSpan>,
+    start: usize,
+    end: usize,
+}
+
+impl<'a> Spans<'a> {
+    /// Iterates over `text`, which starts at index `start` and ends at index `end` of the spans
+    pub(crate) fn new(text: &'a str, spans: &'a [StyleSpan], start: usize, end: usize) -> Self {
+        Self {
+            text,
+            spans: spans.iter(),
+            start,
+            end,
+        }
+    }
+}
+
+impl<'a> Iterator for Spans<'a> {
+    type Item = (&'a str, Style);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let span = self.spans.next()?;
+        let size = self
+            .text
+            .char_indices()
+            .nth(span.end.min(self.end) - span.start.max(self.start))
+            .map_or(self.text.len(), |(byte, _)| byte);
+
+        let (run, rest) = self.text.split_at(size);
+        self.text = rest;
+        Some((run, span.style))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.spans.size_hint()
+    }
+}
+
+/// Iterator over the characters of a [RichString](crate::RichString) and their style
+///
+/// Created by [RichString::styled_chars()](crate::RichString::styled_chars) and
+/// [RichStr::styled_chars()](crate::RichStr::styled_chars).
+#[derive(Debug, Clone)]
+pub struct StyledChars<'a> {
+    chars: Chars<'a>,
+    spans: &'a [StyleSpan],
+    index: usize,
+}
+
+impl<'a> StyledChars<'a> {
+    /// Iterates over `text`, which starts at index `start` of the spans
+    pub(crate) fn new(text: &'a str, spans: &'a [StyleSpan], start: usize) -> Self {
+        Self {
+            chars: text.chars(),
+            spans,
+            index: start,
+        }
+    }
+}
+
+impl Iterator for StyledChars<'_> {
+    type Item = (char, Style);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let c = self.chars.next()?;
+
+        while let Some(span) = self.spans.first() {
+            if self.index < span.end {
+                break;
+            }
+            self.spans = &self.spans[1..];
+        }
+        self.index += 1;
+
+        let style = self.spans.first().map_or(Style::plain(), |span| span.style);
+        Some((c, style))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chars.size_hint()
+    }
+}
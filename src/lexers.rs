@@ -0,0 +1,507 @@
+use crate::{Highlighter, TokenKind};
+use std::ops::Range;
+
+/// Reads source code byte by byte
+///
+/// Tokens only start and end on ASCII characters, which are always on
+/// character boundaries.
+struct Cursor<'a> {
+    source: &'a [u8],
+    position: usize,
+    tokens: Vec<(Range<usize>, TokenKind)>,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            source: source.as_bytes(),
+            position: 0,
+            tokens: Vec::new(),
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.source.get(self.position).copied()
+    }
+
+    fn starts_with(&self, prefix: &[u8]) -> bool {
+        self.source[self.position..].starts_with(prefix)
+    }
+
+    /// Advances while the bytes match a predicate
+    fn eat_while<F: Fn(u8) -> bool>(&mut self, predicate: F) {
+        while self.peek().is_some_and(&predicate) {
+            self.position += 1;
+        }
+    }
+
+    /// Advances past a quoted string, `escapes` telling if backslashes
+    /// escape the next character
+    fn eat_string(&mut self, quote: &[u8], escapes: bool) {
+        self.position += quote.len();
+        while self.position < self.source.len() {
+            if escapes && self.peek() == Some(b'\\') {
+                self.position += 2;
+            } else if self.starts_with(quote) {
+                self.position += quote.len();
+                return;
+            } else {
+                self.position += 1;
+            }
+        }
+        self.position = self.source.len();
+    }
+
+    /// Returns the next byte that isn't a space or a tab
+    fn next_non_blank(&self) -> Option<u8> {
+        self.source[self.position..]
+            .iter()
+            .copied()
+            .find(|b| *b != b' ' && *b != b'\t')
+    }
+
+    fn push(&mut self, start: usize, kind: TokenKind) {
+        if start < self.position {
+            self.tokens.push((start..self.position, kind));
+        }
+    }
+
+    /// Returns the text since `start`
+    fn text(&self, start: usize) -> &'a [u8] {
+        &self.source[start..self.position]
+    }
+}
+
+/// Highlights JSON
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct JsonLexer;
+
+impl Highlighter for JsonLexer {
+    fn tokens(&self, source: &str) -> Vec<(Range<usize>, TokenKind)> {
+        let mut cursor = Cursor::new(source);
+
+        while let Some(byte) = cursor.peek() {
+            let start = cursor.position;
+            match byte {
+                b'"' => {
+                    cursor.eat_string(b"\"", true);
+                    let is_key = cursor
+                        .source
+                        .get(cursor.position..)
+                        .and_then(|rest| rest.iter().find(|b| !b.is_ascii_whitespace()))
+                        == Some(&b':');
+                    let kind = if is_key {
+                        TokenKind::KEY
+                    } else {
+                        TokenKind::STRING
+                    };
+                    cursor.push(start, kind);
+                }
+                b'-' | b'0'..=b'9' => {
+                    cursor.position += 1;
+                    cursor.eat_while(|b| b.is_ascii_digit() || b"+-.eE".contains(&b));
+                    cursor.push(start, TokenKind::NUMBER);
+                }
+                b'a'..=b'z' => {
+                    cursor.eat_while(|b| b.is_ascii_alphanumeric());
+                    if matches!(cursor.text(start), b"true" | b"false" | b"null") {
+                        cursor.push(start, TokenKind::CONSTANT);
+                    }
+                }
+                b'{' | b'}' | b'[' | b']' | b',' | b':' => {
+                    cursor.position += 1;
+                    cursor.push(start, TokenKind::PUNCTUATION);
+                }
+                _ => cursor.position += 1,
+            }
+        }
+
+        cursor.tokens
+    }
+}
+
+/// Highlights TOML
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct TomlLexer;
+
+impl Highlighter for TomlLexer {
+    fn tokens(&self, source: &str) -> Vec<(Range<usize>, TokenKind)> {
+        let mut cursor = Cursor::new(source);
+        // open arrays and inline tables
+        let mut brackets = Vec::new();
+        let mut line_start = true;
+        let mut expect_key = true;
+
+        while let Some(byte) = cursor.peek() {
+            let start = cursor.position;
+            // keys are followed by `=`, or by `.` in dotted keys
+            let is_key = |cursor: &Cursor| {
+                expect_key && matches!(cursor.next_non_blank(), Some(b'=' | b'.'))
+            };
+
+            match byte {
+                b'\n' => {
+                    cursor.position += 1;
+                    line_start = brackets.is_empty();
+                    expect_key = line_start;
+                    continue;
+                }
+                b' ' | b'\t' | b'\r' => {
+                    cursor.position += 1;
+                    continue;
+                }
+                b'#' => {
+                    cursor.eat_while(|b| b != b'\n');
+                    cursor.push(start, TokenKind::COMMENT);
+                }
+                b'[' if line_start => {
+                    cursor.eat_while(|b| b != b'\n' && b != b'#');
+                    while cursor.position > start && cursor.source[cursor.position - 1] != b']' {
+                        cursor.position -= 1;
+                    }
+                    if cursor.position == start {
+                        cursor.position += 1;
+                        cursor.push(start, TokenKind::PUNCTUATION);
+                    } else {
+                        cursor.push(start, TokenKind::KEYWORD);
+                    }
+                }
+                b'"' | b'\'' => {
+                    let escapes = byte == b'"';
+                    if cursor.starts_with(b"\"\"\"") || cursor.starts_with(b"\'\'\'") {
+                        let quote = [byte; 3];
+                        cursor.eat_string(&quote, escapes);
+                    } else {
+                        cursor.eat_string(&[byte], escapes);
+                    }
+                    let kind = if is_key(&cursor) {
+                        TokenKind::KEY
+                    } else {
+                        TokenKind::STRING
+                    };
+                    cursor.push(start, kind);
+                }
+                b'=' => {
+                    cursor.position += 1;
+                    cursor.push(start, TokenKind::OPERATOR);
+                    expect_key = false;
+                }
+                b'[' | b']' | b'{' | b'}' | b',' | b'.' => {
+                    cursor.position += 1;
+                    cursor.push(start, TokenKind::PUNCTUATION);
+                    match byte {
+                        b'[' | b'{' => brackets.push(byte),
+                        b']' | b'}' => {
+                            brackets.pop();
+                        }
+                        _ => {}
+                    }
+                    expect_key = match byte {
+                        b'{' => true,
+                        b',' => brackets.last() == Some(&b'{'),
+                        _ => expect_key,
+                    };
+                }
+                _ if byte.is_ascii_alphanumeric() || b"_-+".contains(&byte) => {
+                    if expect_key {
+                        cursor.eat_while(|b| b.is_ascii_alphanumeric() || b"_-".contains(&b));
+                    } else {
+                        // floats and times are written with dots
+                        cursor.eat_while(|b| b.is_ascii_alphanumeric() || b"_-+:.".contains(&b));
+                    }
+
+                    let text = cursor.text(start);
+                    let kind = if is_key(&cursor) {
+                        TokenKind::KEY
+                    } else if matches!(text, b"true" | b"false") {
+                        TokenKind::CONSTANT
+                    } else if text.iter().skip(1).any(|b| matches!(b, b'-' | b':'))
+                        && text[0].is_ascii_digit()
+                    {
+                        // dates and times
+                        TokenKind::CONSTANT
+                    } else if text[0].is_ascii_digit()
+                        || matches!(
+                            text,
+                            b"inf" | b"nan" | b"+inf" | b"-inf" | b"+nan" | b"-nan"
+                        )
+                        || (b"+-".contains(&text[0]) && text.len() > 1)
+                    {
+                        TokenKind::NUMBER
+                    } else {
+                        line_start = false;
+                        continue;
+                    };
+                    cursor.push(start, kind);
+                }
+                _ => cursor.position += 1,
+            }
+            line_start = false;
+        }
+
+        cursor.tokens
+    }
+}
+
+/// Highlights shell commands, in the syntax of POSIX shells and bash
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ShellLexer;
+
+/// Reserved words of the shell
+const SHELL_KEYWORDS: [&[u8]; 20] = [
+    b"if",
+    b"then",
+    b"else",
+    b"elif",
+    b"fi",
+    b"for",
+    b"while",
+    b"until",
+    b"do",
+    b"done",
+    b"case",
+    b"esac",
+    b"in",
+    b"function",
+    b"select",
+    b"return",
+    b"export",
+    b"local",
+    b"!",
+    b"time",
+];
+
+impl Highlighter for ShellLexer {
+    fn tokens(&self, source: &str) -> Vec<(Range<usize>, TokenKind)> {
+        let mut cursor = Cursor::new(source);
+        // the next word is a command
+        let mut command = true;
+        // `in` is a keyword after `for`, `case` and `select`
+        let mut expect_in = false;
+
+        while let Some(byte) = cursor.peek() {
+            let start = cursor.position;
+            match byte {
+                b'\n' | b';' | b'|' | b'&' | b'(' | b')' => {
+                    cursor.eat_while(|b| b == byte && b != b'(' && b != b')');
+                    if byte != b'\n' {
+                        if cursor.position == start {
+                            cursor.position += 1;
+                        }
+                        cursor.push(start, TokenKind::OPERATOR);
+                    }
+                    command = true;
+                }
+                b'<' | b'>' => {
+                    cursor.eat_while(|b| b == b'<' || b == b'>' || b == b'&');
+                    cursor.push(start, TokenKind::OPERATOR);
+                }
+                b' ' | b'\t' | b'\r' => cursor.position += 1,
+                b'#' => {
+                    cursor.eat_while(|b| b != b'\n');
+                    cursor.push(start, TokenKind::COMMENT);
+                }
+                b'\'' => {
+                    cursor.eat_string(b"'", false);
+                    cursor.push(start, TokenKind::STRING);
+                    command = false;
+                }
+                b'"' => {
+                    cursor.eat_string(b"\"", true);
+                    cursor.push(start, TokenKind::STRING);
+                    command = false;
+                }
+                b'$' => {
+                    cursor.position += 1;
+                    match cursor.peek() {
+                        Some(b'{') => {
+                            cursor.eat_while(|b| b != b'}');
+                            cursor.position = (cursor.position + 1).min(cursor.source.len());
+                        }
+                        Some(b'(') => {
+                            cursor.position += 1;
+                            cursor.push(start, TokenKind::OPERATOR);
+                            command = true;
+                            continue;
+                        }
+                        Some(b) if b.is_ascii_alphabetic() || b == b'_' => {
+                            cursor.eat_while(|b| b.is_ascii_alphanumeric() || b == b'_')
+                        }
+                        Some(b) if b.is_ascii_digit() || b"?!#@*$-".contains(&b) => {
+                            cursor.position += 1
+                        }
+                        _ => {}
+                    }
+                    cursor.push(start, TokenKind::VARIABLE);
+                    command = false;
+                }
+                _ => {
+                    cursor.eat_while(|b| !b.is_ascii_whitespace() && !b"|&;<>()'\"$=".contains(&b));
+                    let word = cursor.text(start);
+                    let is_name = !word.is_empty()
+                        && word.iter().all(|b| b.is_ascii_alphanumeric() || *b == b'_')
+                        && !word[0].is_ascii_digit();
+
+                    if word.is_empty() {
+                        // `=` outside of an assignment
+                        cursor.position += 1;
+                        command = false;
+                    } else if command && is_name && cursor.peek() == Some(b'=') {
+                        cursor.push(start, TokenKind::VARIABLE);
+                        let equals = cursor.position;
+                        cursor.position += 1;
+                        cursor.push(equals, TokenKind::OPERATOR);
+                        cursor.eat_while(|b| {
+                            !b.is_ascii_whitespace() && !b"|&;<>()'\"$".contains(&b)
+                        });
+                    } else if (command && SHELL_KEYWORDS.contains(&word))
+                        || (expect_in && word == b"in")
+                    {
+                        cursor.push(start, TokenKind::KEYWORD);
+                        expect_in = matches!(word, b"for" | b"case" | b"select");
+                        command = !expect_in && word != b"in";
+                    } else if command {
+                        cursor.push(start, TokenKind::FUNCTION);
+                        command = false;
+                    } else if word.iter().all(u8::is_ascii_digit) {
+                        cursor.push(start, TokenKind::NUMBER);
+                    }
+                }
+            }
+        }
+
+        cursor.tokens
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the text and kind of each token
+    fn tokens<H: Highlighter>(highlighter: H, source: &str) -> Vec<(&str, TokenKind)> {
+        highlighter
+            .tokens(source)
+            .into_iter()
+            .map(|(range, kind)| (&source[range], kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_json() {
+        use TokenKind::*;
+
+        assert_eq!(
+            tokens(JsonLexer, r#"{"név": ["a\"b", -1.5e3, true, null]}"#),
+            vec![
+                ("{", PUNCTUATION),
+                (r#""név""#, KEY),
+                (":", PUNCTUATION),
+                ("[", PUNCTUATION),
+                (r#""a\"b""#, STRING),
+                (",", PUNCTUATION),
+                ("-1.5e3", NUMBER),
+                (",", PUNCTUATION),
+                ("true", CONSTANT),
+                (",", PUNCTUATION),
+                ("null", CONSTANT),
+                ("]", PUNCTUATION),
+                ("}", PUNCTUATION),
+            ]
+        );
+        assert_eq!(tokens(JsonLexer, "\"open"), vec![("\"open", STRING)]);
+    }
+
+    #[test]
+    fn test_toml() {
+        use TokenKind::*;
+
+        let source = "# config\n[package]\nname = \"perroquet\" # inline\nsite.url = 'x'\n\
+                      [[bin]]\nversion = 1.5\ndate = 1979-05-27T07:32:00Z\nflags = [true, -3]\n\
+                      inline = { a = \"\"\"b\"\"\" }\n";
+
+        assert_eq!(
+            tokens(TomlLexer, source),
+            vec![
+                ("# config", COMMENT),
+                ("[package]", KEYWORD),
+                ("name", KEY),
+                ("=", OPERATOR),
+                ("\"perroquet\"", STRING),
+                ("# inline", COMMENT),
+                ("site", KEY),
+                (".", PUNCTUATION),
+                ("url", KEY),
+                ("=", OPERATOR),
+                ("'x'", STRING),
+                ("[[bin]]", KEYWORD),
+                ("version", KEY),
+                ("=", OPERATOR),
+                ("1.5", NUMBER),
+                ("date", KEY),
+                ("=", OPERATOR),
+                ("1979-05-27T07:32:00Z", CONSTANT),
+                ("flags", KEY),
+                ("=", OPERATOR),
+                ("[", PUNCTUATION),
+                ("true", CONSTANT),
+                (",", PUNCTUATION),
+                ("-3", NUMBER),
+                ("]", PUNCTUATION),
+                ("inline", KEY),
+                ("=", OPERATOR),
+                ("{", PUNCTUATION),
+                ("a", KEY),
+                ("=", OPERATOR),
+                ("\"\"\"b\"\"\"", STRING),
+                ("}", PUNCTUATION),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shell() {
+        use TokenKind::*;
+
+        let source = "FOO=1 cargo build --release 2>&1 | grep \"error\" # check\n\
+                      if [ -n ${BAR} ]; then echo '$x' $HOME; fi\n\
+                      for f in $(ls); do rm $f; done";
+
+        assert_eq!(
+            tokens(ShellLexer, source),
+            vec![
+                ("FOO", VARIABLE),
+                ("=", OPERATOR),
+                ("cargo", FUNCTION),
+                ("2", NUMBER),
+                (">&", OPERATOR),
+                ("1", NUMBER),
+                ("|", OPERATOR),
+                ("grep", FUNCTION),
+                ("\"error\"", STRING),
+                ("# check", COMMENT),
+                ("if", KEYWORD),
+                ("[", FUNCTION),
+                ("${BAR}", VARIABLE),
+                (";", OPERATOR),
+                ("then", KEYWORD),
+                ("echo", FUNCTION),
+                ("'$x'", STRING),
+                ("$HOME", VARIABLE),
+                (";", OPERATOR),
+                ("fi", KEYWORD),
+                ("for", KEYWORD),
+                ("in", KEYWORD),
+                ("$(", OPERATOR),
+                ("ls", FUNCTION),
+                (")", OPERATOR),
+                (";", OPERATOR),
+                ("do", KEYWORD),
+                ("rm", FUNCTION),
+                ("$f", VARIABLE),
+                (";", OPERATOR),
+                ("done", KEYWORD),
+            ]
+        );
+    }
+}
@@ -5,14 +5,88 @@
 //!
 //! Made to work with [textflow](https://docs.rs/textflow)
 
-extern crate substring;
-
 mod style;
 pub use style::Style;
 
 mod string;
 pub use string::RichString;
 
+mod text;
+pub use text::RichText;
+
+mod slice;
+pub use slice::RichStr;
+
+mod error;
+pub use error::{IndexError, ParseStyleError, ThemeError};
+
+mod theme;
+pub use theme::Theme;
+
+mod dircolors;
+pub use dircolors::{FileType, LsColors};
+
+mod accessibility;
+pub use accessibility::ColorBlindness;
+
+mod gradient;
+pub use gradient::Gradient;
+
+mod wrap;
+pub use wrap::Alignment;
+
+mod columns;
+pub use columns::{Columns, Constraint};
+
+mod tree;
+pub use tree::{Guides, Tree};
+
+mod border;
+pub use border::Border;
+
+mod panel;
+pub use panel::Panel;
+
+mod table;
+pub use table::{Overflow, Table};
+
+mod diagnostic;
+pub use diagnostic::{Diagnostic, Severity};
+
+mod diff;
+pub use diff::Diff;
+
+mod highlight;
+pub use highlight::{Highlighter, TokenKind};
+
+mod lexers;
+pub use lexers::{JsonLexer, ShellLexer, TomlLexer};
+
+#[cfg(feature = "syntect")]
+mod syntax;
+#[cfg(feature = "syntect")]
+pub use syntax::SyntectHighlighter;
+
+mod markdown;
+pub use markdown::Markdown;
+
+mod progress;
+pub use progress::{ProgressBar, ProgressState, Spinner};
+
+mod writer;
+pub use writer::StyledWriter;
+
+mod ansi;
+mod interop;
+mod rgb;
+
+mod index;
+mod iter;
+pub use iter::{Spans, StyledChars};
+
+#[cfg(feature = "serde")]
+mod serialize;
+
 mod attributes;
 pub use attributes::{Color, Decoration, Feature};
 
@@ -0,0 +1,1005 @@
+use crate::{
+    Highlighter, JsonLexer, RichString, RichText, ShellLexer, Style, Theme, TokenKind, TomlLexer,
+};
+
+/// Bullets of unordered lists, by nesting level
+const BULLETS: [&str; 3] = ["•", "◦", "▪"];
+
+/// A block of a Markdown document
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Block {
+    /// A heading with its level, from 1 to 6
+    Heading(usize, String),
+    /// A paragraph, hard line breaks being newlines
+    Paragraph(String),
+    /// A code block with its language, possibly empty
+    Code(String, Vec<String>),
+    /// A block quote
+    Quote(Vec<Block>),
+    /// An ordered list if it has a start number, or an unordered one
+    List {
+        start: Option<usize>,
+        tight: bool,
+        items: Vec<Vec<Block>>,
+    },
+    /// A horizontal rule
+    Rule,
+}
+
+/// The marker at the beginning of a list item
+struct Marker {
+    ordered: bool,
+    number: usize,
+    /// Column where the content of the item starts
+    content: usize,
+}
+
+/// Returns the number of spaces at the beginning of a line
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+fn is_blank(line: &str) -> bool {
+    line.trim().is_empty()
+}
+
+/// Returns the character and length of a code fence
+fn fence(line: &str) -> Option<(char, usize)> {
+    if indentation(line) > 3 {
+        return None;
+    }
+
+    let trimmed = line.trim_start();
+    let c = trimmed.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let length = trimmed.chars().take_while(|x| *x == c).count();
+
+    if length < 3 || (c == '`' && trimmed[length..].contains('`')) {
+        None
+    } else {
+        Some((c, length))
+    }
+}
+
+/// Returns the level and text of an ATX heading, like `## Usage`
+fn heading(line: &str) -> Option<(usize, &str)> {
+    if indentation(line) > 3 {
+        return None;
+    }
+
+    let trimmed = line.trim_start();
+    let level = trimmed.chars().take_while(|c| *c == '#').count();
+    let rest = &trimmed[level..];
+    if !(1..=6).contains(&level) || !(rest.is_empty() || rest.starts_with(' ')) {
+        return None;
+    }
+
+    // the closing sequence of `#` is optional
+    let text = rest.trim();
+    let without_closing = text.trim_end_matches('#');
+    if without_closing.is_empty() || without_closing.ends_with(' ') {
+        Some((level, without_closing.trim_end()))
+    } else {
+        Some((level, text))
+    }
+}
+
+fn is_rule(line: &str) -> bool {
+    let chars: Vec<char> = line.chars().filter(|c| !c.is_whitespace()).collect();
+
+    indentation(line) <= 3
+        && chars.len() >= 3
+        && matches!(chars[0], '-' | '*' | '_')
+        && chars.iter().all(|c| *c == chars[0])
+}
+
+fn is_quote(line: &str) -> bool {
+    indentation(line) <= 3 && line.trim_start().starts_with('>')
+}
+
+/// Parses the marker of a list item, like `-` or `1.`
+fn list_marker(line: &str) -> Option<Marker> {
+    let indent = indentation(line);
+    if indent > 3 {
+        return None;
+    }
+
+    let rest = &line[indent..];
+    let (ordered, number, length) = if rest.starts_with(['-', '*', '+']) {
+        (false, 0, 1)
+    } else {
+        let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+        if digits == 0 || digits > 9 || !matches!(rest.as_bytes().get(digits), Some(b'.' | b')')) {
+            return None;
+        }
+        (true, rest[..digits].parse().ok()?, digits + 1)
+    };
+
+    let after = &rest[length..];
+    if !after.is_empty() && !after.starts_with(' ') {
+        return None;
+    }
+    // content indented too much is code, which keeps its extra spaces
+    let spaces = match indentation(after) {
+        spaces @ 1..=4 if !is_blank(after) => spaces,
+        _ => 1,
+    };
+
+    Some(Marker {
+        ordered,
+        number,
+        content: indent + length + spaces,
+    })
+}
+
+/// Returns `true` if the line starts a block that ends a paragraph
+fn interrupts(line: &str) -> bool {
+    heading(line).is_some()
+        || fence(line).is_some()
+        || is_rule(line)
+        || is_quote(line)
+        || list_marker(line)
+            .is_some_and(|marker| !is_blank(line.get(marker.content..).unwrap_or("")))
+}
+
+/// Splits lines into blocks
+fn parse_blocks(lines: &[String]) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = &lines[i];
+
+        if is_blank(line) {
+            i += 1;
+        } else if let Some((c, length)) = fence(line) {
+            let indent = indentation(line);
+            let language = line.trim_start()[length..]
+                .split_whitespace()
+                .next()
+                .unwrap_or("")
+                .to_string();
+            let mut code = Vec::new();
+
+            i += 1;
+            while i < lines.len() {
+                let line = &lines[i];
+                i += 1;
+                if let Some((closing, closing_length)) = fence(line) {
+                    if closing == c
+                        && closing_length >= length
+                        && is_blank(&line.trim_start()[closing_length..])
+                    {
+                        break;
+                    }
+                }
+                code.push(line[indentation(line).min(indent)..].to_string());
+            }
+
+            blocks.push(Block::Code(language, code));
+        } else if indentation(line) >= 4 {
+            let mut code = Vec::new();
+
+            while i < lines.len() && (is_blank(&lines[i]) || indentation(&lines[i]) >= 4) {
+                code.push(lines[i].get(4..).unwrap_or("").to_string());
+                i += 1;
+            }
+            while code.last().is_some_and(|line| is_blank(line)) {
+                code.pop();
+            }
+
+            blocks.push(Block::Code(String::new(), code));
+        } else if let Some((level, text)) = heading(line) {
+            blocks.push(Block::Heading(level, text.to_string()));
+            i += 1;
+        } else if is_rule(line) {
+            blocks.push(Block::Rule);
+            i += 1;
+        } else if is_quote(line) {
+            let mut quoted: Vec<String> = Vec::new();
+
+            while i < lines.len() {
+                let line = &lines[i];
+                if is_quote(line) {
+                    let rest = &line.trim_start()[1..];
+                    quoted.push(rest.strip_prefix(' ').unwrap_or(rest).to_string());
+                } else if !is_blank(line)
+                    && !interrupts(line)
+                    && quoted.last().is_some_and(|line| !is_blank(line))
+                {
+                    // lazy continuation of a paragraph
+                    quoted.push(line.to_string());
+                } else {
+                    break;
+                }
+                i += 1;
+            }
+
+            blocks.push(Block::Quote(parse_blocks(&quoted)));
+        } else if let Some(first) = list_marker(line) {
+            let mut items = Vec::new();
+            let mut tight = true;
+            let mut blank_before = false;
+
+            while let Some(marker) = lines.get(i).and_then(|line| list_marker(line)) {
+                if marker.ordered != first.ordered {
+                    break;
+                }
+                if blank_before {
+                    tight = false;
+                }
+
+                let mut item = vec![lines[i].get(marker.content..).unwrap_or("").to_string()];
+                i += 1;
+                while i < lines.len() {
+                    let line = &lines[i];
+                    if is_blank(line) {
+                        item.push(String::new());
+                    } else if indentation(line) >= marker.content {
+                        item.push(line[marker.content..].to_string());
+                    } else if item.last().is_some_and(|line| !is_blank(line)) && !interrupts(line) {
+                        item.push(line.trim_start().to_string());
+                    } else {
+                        break;
+                    }
+                    i += 1;
+                }
+
+                blank_before = false;
+                while item.last().is_some_and(|line| is_blank(line)) {
+                    item.pop();
+                    blank_before = true;
+                }
+
+                let has_blank = item.iter().any(|line| is_blank(line));
+                let item = parse_blocks(&item);
+                if has_blank && item.len() > 1 {
+                    tight = false;
+                }
+                items.push(item);
+            }
+
+            blocks.push(Block::List {
+                start: first.ordered.then_some(first.number),
+                tight,
+                items,
+            });
+        } else {
+            let mut text = String::new();
+            let mut level = None;
+
+            while i < lines.len() {
+                let line = &lines[i];
+                if is_blank(line) {
+                    break;
+                }
+
+                let trimmed = line.trim();
+                if !text.is_empty() {
+                    if indentation(line) <= 3
+                        && (trimmed.chars().all(|c| c == '=') || trimmed.chars().all(|c| c == '-'))
+                    {
+                        level = Some(if trimmed.starts_with('=') { 1 } else { 2 });
+                        i += 1;
+                        break;
+                    } else if interrupts(line) {
+                        break;
+                    }
+
+                    // two spaces or a backslash at the end of a line break it
+                    if text.ends_with("  ") {
+                        text.truncate(text.trim_end().len());
+                        text.push('\n');
+                    } else if text.ends_with('\\') {
+                        text.pop();
+                        text.push('\n');
+                    } else {
+                        text.truncate(text.trim_end().len());
+                        text.push(' ');
+                    }
+                }
+                text.push_str(line.trim_start());
+                i += 1;
+            }
+
+            let text = text.trim_end().to_string();
+            blocks.push(match level {
+                Some(level) => Block::Heading(level, text),
+                None => Block::Paragraph(text),
+            });
+        }
+    }
+
+    blocks
+}
+
+/// Returns a highlighter for the language of a code block
+fn highlighter(language: &str) -> Option<Box<dyn Highlighter>> {
+    match language.to_lowercase().as_str() {
+        "" => None,
+        "json" => Some(Box::new(JsonLexer)),
+        "toml" => Some(Box::new(TomlLexer)),
+        "sh" | "bash" | "shell" | "zsh" => Some(Box::new(ShellLexer)),
+        #[cfg(feature = "syntect")]
+        _ => crate::SyntectHighlighter::from_name(language)
+            .map(|highlighter| Box::new(highlighter) as Box<dyn Highlighter>),
+        #[cfg(not(feature = "syntect"))]
+        _ => None,
+    }
+}
+
+/// Percent-encodes the control characters of a URL
+fn escape_url(url: &str) -> String {
+    let mut escaped = String::with_capacity(url.len());
+    for c in url.chars() {
+        if c.is_control() {
+            let mut bytes = [0; 4];
+            for byte in c.encode_utf8(&mut bytes).bytes() {
+                escaped.push_str(&format!("%{:02X}", byte));
+            }
+        } else {
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+/// Finds the end of a code span starting with backticks
+fn code_span_end(text: &str) -> Option<usize> {
+    let ticks = &text[..text.len() - text.trim_start_matches('`').len()];
+    let mut search = ticks.len();
+
+    while let Some(found) = text[search..].find(ticks) {
+        let position = search + found;
+        let length = text[position..].len() - text[position..].trim_start_matches('`').len();
+
+        if length == ticks.len() {
+            return Some(position + length);
+        }
+        search = position + length;
+    }
+
+    None
+}
+
+/// Finds the `]` and the end of a link starting with `[`
+fn link_end(text: &str) -> Option<(usize, usize)> {
+    let bytes = text.as_bytes();
+    let mut depth = 0;
+    let mut position = 0;
+    let mut close = None;
+
+    while position < bytes.len() {
+        match bytes[position] {
+            b'\\' => position += 1,
+            b'[' => depth += 1,
+            b']' => {
+                depth -= 1;
+                if depth == 0 {
+                    close = Some(position);
+                    break;
+                }
+            }
+            _ => {}
+        }
+        position += 1;
+    }
+
+    let close = close?;
+    let destination = text[close + 1..].strip_prefix('(')?;
+    let mut depth = 1;
+    let end = destination.bytes().position(|b| {
+        match b {
+            b'(' => depth += 1,
+            b')' => depth -= 1,
+            _ => {}
+        }
+        depth == 0
+    })?;
+
+    Some((close, close + 2 + end + 1))
+}
+
+/// Turns blocks into lines
+///
+/// Links are named `markdown.link.<n>`, `n` being their index in `links`,
+/// so they can be found after wrapping. The other inline styles are
+/// resolved right away, because they can be nested.
+struct Renderer<'a> {
+    theme: &'a Theme,
+    show_urls: bool,
+    links: Vec<String>,
+}
+
+impl Renderer<'_> {
+    fn blocks(
+        &mut self,
+        blocks: &[Block],
+        width: usize,
+        tight: bool,
+        depth: usize,
+    ) -> Vec<RichString> {
+        let mut lines = Vec::new();
+
+        for (index, block) in blocks.iter().enumerate() {
+            if index > 0 && !tight {
+                lines.push(RichString::new());
+            }
+            lines.extend(self.block(block, width, depth));
+        }
+
+        lines
+    }
+
+    fn block(&mut self, block: &Block, width: usize, depth: usize) -> Vec<RichString> {
+        match block {
+            Block::Heading(level, text) => {
+                let style = self.theme.get(&format!("markdown.heading.{}", level));
+                self.inline(text, style).wrap(width)
+            }
+            Block::Paragraph(text) => self.inline(text, Style::plain()).wrap(width),
+            Block::Code(language, code) => {
+                let source = code.join("\n");
+                let highlighted = match highlighter(language) {
+                    Some(highlighter) => highlighter.highlight_with(&source, self.theme),
+                    None => RichString::from(&source, Style::plain()),
+                };
+                let style = self.theme.get("markdown.code_block");
+
+                // the indentation shrinks to leave at least one column of code
+                let indentation = 2.min(width.saturating_sub(1));
+
+                highlighted
+                    .split("\n")
+                    .into_iter()
+                    .map(|line| {
+                        let mut indented =
+                            RichString::from(&" ".repeat(indentation), Style::plain());
+                        indented.push(&line.ellipsize(width - indentation));
+                        indented.into_complemented(style)
+                    })
+                    .collect()
+            }
+            Block::Quote(blocks) => {
+                // the bar shrinks to leave at least one column of text
+                let bar = RichString::from("│ ", self.theme.get("markdown.quote.bar"))
+                    .truncate_width(width.saturating_sub(1));
+                let style = self.theme.get("markdown.quote");
+
+                self.blocks(blocks, width - bar.width(), false, depth)
+                    .into_iter()
+                    .map(|line| bar.clone() + line.into_complemented(style))
+                    .collect()
+            }
+            Block::List {
+                start,
+                tight,
+                items,
+            } => {
+                let markers: Vec<String> = (0..items.len())
+                    .map(|index| match start {
+                        Some(start) => format!("{}.", start + index),
+                        None => String::from(BULLETS[depth % BULLETS.len()]),
+                    })
+                    .collect();
+                let marker_width = markers
+                    .iter()
+                    .map(|marker| marker.chars().count())
+                    .max()
+                    .unwrap_or(0);
+                let style = self.theme.get("markdown.list");
+                let mut lines = Vec::new();
+
+                for (index, (item, marker)) in items.iter().zip(markers).enumerate() {
+                    if index > 0 && !tight {
+                        lines.push(RichString::new());
+                    }
+
+                    // the marker shrinks to leave at least one column of text
+                    let marker =
+                        (RichString::from(&format!("{:>1$}", marker, marker_width), style) + " ")
+                            .truncate_width(width.saturating_sub(1));
+                    let content = self.blocks(item, width - marker.width(), *tight, depth + 1);
+                    if content.is_empty() {
+                        lines.push(marker.clone());
+                    }
+                    for (row, line) in content.into_iter().enumerate() {
+                        if row == 0 {
+                            lines.push(marker.clone() + line);
+                        } else if line.is_empty() {
+                            lines.push(line);
+                        } else {
+                            lines.push(
+                                RichString::from(&" ".repeat(marker.width()), Style::plain())
+                                    + line,
+                            );
+                        }
+                    }
+                }
+
+                lines
+            }
+            Block::Rule => vec![RichString::from(
+                &"─".repeat(width),
+                self.theme.get("markdown.rule"),
+            )],
+        }
+    }
+
+    /// Parses the inline elements of a text, like emphasis or links
+    fn inline(&mut self, text: &str, style: Style) -> RichString {
+        let mut output = RichString::new();
+        let mut buffer = String::new();
+        let mut rest = text;
+
+        while let Some(c) = rest.chars().next() {
+            let previous = text[..text.len() - rest.len()].chars().next_back();
+            let parsed = match c {
+                '\\' => match rest[1..].chars().next() {
+                    Some(escaped) if escaped.is_ascii_punctuation() => {
+                        buffer.push(escaped);
+                        rest = &rest[2..];
+                        continue;
+                    }
+                    _ => None,
+                },
+                '`' => self.code_span(rest, style),
+                '*' | '_' | '~' => self.delimited(rest, style, previous),
+                '[' => self.link(rest, style),
+                '!' if rest[1..].starts_with('[') => self
+                    .link(&rest[1..], style)
+                    .map(|(image, length)| (image, length + 1)),
+                '<' => self.autolink(rest, style),
+                _ => None,
+            };
+
+            match parsed {
+                Some((piece, length)) => {
+                    output.push(&RichString::from(&buffer, style));
+                    output.push(&piece);
+                    buffer.clear();
+                    rest = &rest[length..];
+                }
+                None => {
+                    // a run of delimiters that doesn't open anything is text
+                    let length = match c {
+                        '`' | '*' | '_' | '~' => rest.len() - rest.trim_start_matches(c).len(),
+                        _ => c.len_utf8(),
+                    };
+                    buffer.push_str(&rest[..length]);
+                    rest = &rest[length..];
+                }
+            }
+        }
+
+        output.push(&RichString::from(&buffer, style));
+        output
+    }
+
+    /// Parses a code span, like `` `cargo` ``
+    fn code_span(&self, text: &str, style: Style) -> Option<(RichString, usize)> {
+        let ticks = text.len() - text.trim_start_matches('`').len();
+        let end = code_span_end(text)?;
+
+        let mut code = text[ticks..end - ticks].replace('\n', " ");
+        if code.len() >= 2 && code.starts_with(' ') && code.ends_with(' ') && !is_blank(&code) {
+            code = code[1..code.len() - 1].to_string();
+        }
+        let code = RichString::from(&code, self.theme.get("markdown.code") & style);
+        Some((code, end))
+    }
+
+    /// Parses emphasis, strong or struck text
+    fn delimited(
+        &mut self,
+        text: &str,
+        style: Style,
+        previous: Option<char>,
+    ) -> Option<(RichString, usize)> {
+        let c = text.chars().next()?;
+        let run = text.len() - text.trim_start_matches(c).len();
+        // underscores inside words are not delimiters
+        let intraword = |c: Option<char>| c.is_some_and(char::is_alphanumeric);
+        if c == '_' && intraword(previous) {
+            return None;
+        }
+
+        let (size, name) = match c {
+            '~' if run >= 2 => (2, "markdown.strikethrough"),
+            '~' => return None,
+            _ if run >= 2 => (2, "markdown.strong"),
+            _ => (1, "markdown.emphasis"),
+        };
+        if text[size..].starts_with(char::is_whitespace) || text.len() == size {
+            return None;
+        }
+
+        // escaped characters, code spans and links can't hold the closer
+        let mut position = run;
+        while let Some(x) = text[position..].chars().next() {
+            let rest = &text[position..];
+            let length = rest.len() - rest.trim_start_matches(x).len();
+
+            position += match x {
+                '\\' => 1 + rest[1..].chars().next().map_or(0, char::len_utf8),
+                '`' => code_span_end(rest).unwrap_or(length),
+                '[' => link_end(rest).map_or(1, |(_, end)| end),
+                _ if x == c => {
+                    let end = position + length;
+                    let closes = (length == size || length >= 3)
+                        && end - size > size
+                        && !text[..position].ends_with(char::is_whitespace)
+                        && !(c == '_' && intraword(text[end..].chars().next()));
+
+                    if closes {
+                        let inner = &text[size..end - size];
+                        return Some((self.inline(inner, self.theme.get(name) & style), end));
+                    }
+                    length
+                }
+                _ => x.len_utf8(),
+            };
+        }
+
+        None
+    }
+
+    /// Parses a link like `[text](url)`, or an image
+    fn link(&mut self, text: &str, style: Style) -> Option<(RichString, usize)> {
+        let (close, end) = link_end(text)?;
+
+        // the title of the link is not shown
+        let destination = &text[close + 2..end - 1];
+        let url = destination.split_whitespace().next().unwrap_or("");
+        let url = url
+            .strip_prefix('<')
+            .and_then(|url| url.strip_suffix('>'))
+            .unwrap_or(url);
+
+        let label = self.inline(&text[1..close], style);
+        Some((self.hyperlink(label, url), end))
+    }
+
+    /// Parses an autolink, like `<https://docs.rs>`
+    fn autolink(&mut self, text: &str, style: Style) -> Option<(RichString, usize)> {
+        let end = text.find('>')?;
+        let inner = &text[1..end];
+        if inner.is_empty() || inner.contains(char::is_whitespace) {
+            return None;
+        }
+
+        let url = if inner.contains("://") || inner.starts_with("mailto:") {
+            inner.to_string()
+        } else if inner.contains('@') {
+            format!("mailto:{}", inner)
+        } else {
+            return None;
+        };
+
+        Some((
+            self.hyperlink(RichString::from(inner, style), &url),
+            end + 1,
+        ))
+    }
+
+    fn hyperlink(&mut self, mut label: RichString, url: &str) -> RichString {
+        label.set_name(&format!("markdown.link.{}", self.links.len()));
+        self.links.push(url.to_string());
+
+        if self.show_urls && label.raw() != url && url.strip_prefix("mailto:") != Some(label.raw())
+        {
+            label.push(&RichString::named(&format!(" <{}>", url), "markdown.url"));
+        }
+        label
+    }
+}
+
+/// A Markdown document, rendered for terminals
+///
+/// Headings, emphasis, strong and struck text, inline code, code blocks,
+/// lists, block quotes, links and horizontal rules are supported.
+/// Paragraphs are wrapped to a width, while code blocks are cut, and
+/// highlighted if their language is known.
+///
+/// The styles come from a theme, with the names `markdown.heading.1` to
+/// `markdown.heading.6` (inheriting from `markdown.heading`),
+/// `markdown.emphasis`, `markdown.strong`, `markdown.strikethrough`,
+/// `markdown.code`, `markdown.code_block`, `markdown.link`, `markdown.url`,
+/// `markdown.quote`, `markdown.quote.bar`, `markdown.list` for the bullets
+/// and numbers, and `markdown.rule`. Code blocks also use the names of
+/// [TokenKind::style_name()].
+///
+/// ```
+/// # use perroquet::*;
+/// let markdown = Markdown::new("# Usage\n\n- run `cargo build`\n- *then* test").width(20);
+///
+/// let text = markdown.render();
+/// let lines: Vec<&str> = text.lines().iter().map(RichString::raw).collect();
+/// assert_eq!(lines, ["Usage", "", "• run cargo build", "• then test"]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Markdown {
+    blocks: Vec<Block>,
+    width: usize,
+    show_urls: bool,
+}
+
+impl Markdown {
+    /// Parses a Markdown document
+    pub fn new(source: &str) -> Self {
+        let lines: Vec<String> = source
+            .lines()
+            .map(|line| line.replace('\t', "    "))
+            .collect();
+
+        Self {
+            blocks: parse_blocks(&lines),
+            width: 80,
+            show_urls: false,
+        }
+    }
+
+    /// Sets the number of columns to wrap the text to, 80 by default
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width.max(1);
+        self
+    }
+
+    /// Writes the URL of links after their text, for terminals that don't
+    /// support hyperlinks
+    pub fn show_urls(mut self, show: bool) -> Self {
+        self.show_urls = show;
+        self
+    }
+
+    /// Returns the default theme, which includes the one of
+    /// [TokenKind::default_theme()]
+    pub fn default_theme() -> Theme {
+        let style = |style: &str| style.parse::<Style>().unwrap_or_default();
+
+        TokenKind::default_theme()
+            .with("markdown.heading", style("bold"))
+            .with("markdown.heading.1", style("purple underline"))
+            .with("markdown.heading.2", style("purple"))
+            .with("markdown.emphasis", style("italic"))
+            .with("markdown.strong", style("bold"))
+            .with("markdown.strikethrough", style("linethrough"))
+            .with("markdown.code", style("yellow"))
+            .with("markdown.link", style("blue underline"))
+            .with("markdown.url", style("darkgrey"))
+            .with("markdown.quote", style("italic"))
+            .with("markdown.quote.bar", style("darkgrey"))
+            .with("markdown.list", style("cyan"))
+            .with("markdown.rule", style("darkgrey"))
+    }
+
+    /// Lays out the document, with links still named
+    fn layout(&self, theme: &Theme) -> (Vec<RichString>, Vec<String>) {
+        let mut renderer = Renderer {
+            theme,
+            show_urls: self.show_urls,
+            links: Vec::new(),
+        };
+        let lines = renderer.blocks(&self.blocks, self.width, false, 0);
+
+        (lines, renderer.links)
+    }
+
+    /// Renders the document with the default theme
+    pub fn render(&self) -> RichText {
+        self.render_with(&Self::default_theme())
+    }
+
+    /// Renders the document with the styles of a theme
+    ///
+    /// Links are styled but can't be followed, see [Self::to_ansi_with()]
+    /// for that.
+    pub fn render_with(&self, theme: &Theme) -> RichText {
+        let (lines, _) = self.layout(theme);
+
+        lines
+            .into_iter()
+            .map(|line| line.into_resolved(theme))
+            .collect()
+    }
+
+    /// Renders the document for a terminal, with the default theme
+    pub fn to_ansi(&self) -> String {
+        self.to_ansi_with(&Self::default_theme())
+    }
+
+    /// Renders the document for a terminal, with the styles of a theme
+    ///
+    /// This is like [RichText::to_ansi()], but links are also written as
+    /// OSC 8 hyperlinks, which many terminals let you click. Control
+    /// characters in their URLs are percent-encoded, so that they can't end
+    /// the escape sequence early.
+    pub fn to_ansi_with(&self, theme: &Theme) -> String {
+        let (lines, links) = self.layout(theme);
+        let mut output = Vec::with_capacity(lines.len());
+
+        for line in lines {
+            let link_at = |index: usize| {
+                line.name_at(index)
+                    .and_then(|name| name.strip_prefix("markdown.link."))
+                    .and_then(|index| index.parse::<usize>().ok())
+            };
+            let mut ansi = String::new();
+            let mut start = 0;
+
+            while start < line.len() {
+                let link = link_at(start);
+                let mut end = start + 1;
+                while end < line.len() && link_at(end) == link {
+                    end += 1;
+                }
+
+                let piece = line.substring(start, end).into_resolved(theme).to_ansi();
+                match link {
+                    Some(link) => ansi.push_str(&format!(
+                        "\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\",
+                        escape_url(&links[link]),
+                        piece
+                    )),
+                    None => ansi.push_str(&piece),
+                }
+                start = end;
+            }
+
+            output.push(ansi);
+        }
+
+        output.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shortcuts::*;
+
+    fn raw_lines(text: &RichText) -> Vec<&str> {
+        text.lines().iter().map(RichString::raw).collect()
+    }
+
+    #[test]
+    fn test_render() {
+        let source = "Title\n=====\n\n\
+                      Some *emphasis*, **strong** and ~~old~~ text with `code`,\n\
+                      a [link](https://docs.rs \"Docs\") and an escaped \\*star\\*.\n\n\
+                      > quoted\n> **text**\n\n\
+                      1. first\n2. second\n   - nested\n\n\
+                      ***\n\n\
+                      ```json\n{\"a\": 1}\n```\n\n    indented code";
+        let theme = Theme::new()
+            .with("markdown.heading", BOLD)
+            .with("markdown.emphasis", ITALIC)
+            .with("markdown.strong", RED)
+            .with("markdown.code", YELLOW)
+            .with("markdown.link", BLUE)
+            .with("markdown.list", CYAN)
+            .with("markdown.quote", ITALIC);
+        let text = Markdown::new(source).render_with(&theme);
+
+        assert_eq!(
+            raw_lines(&text),
+            [
+                "Title",
+                "",
+                "Some emphasis, strong and old text with code, a link and an escaped *star*.",
+                "",
+                "│ quoted text",
+                "",
+                "1. first",
+                "2. second",
+                "   ◦ nested",
+                "",
+                "────────────────────────────────────────────────────────────────────────────────",
+                "",
+                "  {\"a\": 1}",
+                "",
+                "  indented code",
+            ]
+        );
+
+        let paragraph = &text.lines()[2];
+        assert_eq!(paragraph.style_at(5), ITALIC);
+        assert_eq!(paragraph.style_at(15), RED);
+        assert_eq!(paragraph.style_at(40), YELLOW);
+        assert_eq!(paragraph.style_at(48), BLUE);
+        assert_eq!(text.lines()[0].style_at(0), BOLD);
+        assert_eq!(text.lines()[4].style_at(2), ITALIC);
+        assert_eq!(text.lines()[4].style_at(10), RED & ITALIC);
+        assert_eq!(text.lines()[6].style_at(0), CYAN);
+
+        let source = "*a\\*b* then *use `x*y`* and *see [a*b](https://docs.rs/*c)* end";
+        let text = Markdown::new(source).render_with(&theme);
+        assert_eq!(raw_lines(&text), ["a*b then use x*y and see a*b end"]);
+        let paragraph = &text.lines()[0];
+        assert_eq!(paragraph.style_at(1), ITALIC);
+        assert_eq!(paragraph.style_at(2), ITALIC);
+        assert_eq!(paragraph.style_at(13), YELLOW & ITALIC);
+        assert_eq!(paragraph.style_at(15), YELLOW & ITALIC);
+        assert_eq!(paragraph.style_at(25), BLUE & ITALIC);
+        assert_eq!(paragraph.style_at(29), Style::plain());
+    }
+
+    #[test]
+    fn test_width() {
+        let source = "## A heading that is long\n\n\
+                      - an item that needs to be wrapped\n\n\
+                      - another\n\n\
+                      ```\nlet code = \"is not wrapped\";\n```";
+        let text = Markdown::new(source).width(16).render();
+
+        assert_eq!(
+            raw_lines(&text),
+            [
+                "A heading that",
+                "is long",
+                "",
+                "• an item that",
+                "  needs to be",
+                "  wrapped",
+                "",
+                "• another",
+                "",
+                "  let code = \"i…",
+            ]
+        );
+
+        let code = Markdown::new("```\nabcdef\n```");
+        assert_eq!(raw_lines(&code.clone().width(1).render()), ["…"]);
+        assert_eq!(raw_lines(&code.width(3).render()), ["  …"]);
+
+        let source = "# Title\n\n10. item\n\n> quoted\n> ```\n> code\n> ```\n\n---";
+        for width in 1..8 {
+            let text = Markdown::new(source).width(width).render();
+            assert!(text.lines().iter().all(|line| line.width() <= width));
+        }
+
+        let text = Markdown::new(
+            "- ab
+
+> cd",
+        )
+        .width(2)
+        .render();
+        assert_eq!(raw_lines(&text), ["•a", " b", "", "│c", "│d"]);
+        let text = Markdown::new(
+            "- ab
+
+> cd",
+        )
+        .width(1)
+        .render();
+        assert_eq!(raw_lines(&text), ["a", "b", "", "c", "d"]);
+    }
+
+    #[test]
+    fn test_show_urls() {
+        let source = "See [the docs](https://docs.rs) or <https://crates.io>.";
+
+        assert_eq!(
+            raw_lines(&Markdown::new(source).show_urls(true).render()),
+            ["See the docs <https://docs.rs> or https://crates.io."]
+        );
+    }
+
+    #[test]
+    fn test_to_ansi() {
+        let theme = Theme::new().with("markdown.link", BLUE);
+
+        assert_eq!(
+            Markdown::new("go to [docs](https://docs.rs)").to_ansi_with(&theme),
+            "go to \x1b]8;;https://docs.rs\x1b\\\x1b[34mdocs\x1b[0m\x1b]8;;\x1b\\"
+        );
+        assert_eq!(
+            Markdown::new("a\n\nb").to_ansi_with(&Theme::new()),
+            "a\n\nb"
+        );
+        assert_eq!(
+            Markdown::new("[x](a\x1b]8;;b\x07c)").to_ansi_with(&Theme::new()),
+            "\x1b]8;;a%1B]8;;b%07c\x1b\\x\x1b]8;;\x1b\\"
+        );
+    }
+}
@@ -0,0 +1,270 @@
+use crate::{Alignment, Border, RichStr, RichString, Style};
+
+/// Text surrounded by a border, with an optional title
+///
+/// ```
+/// # use perroquet::*;
+/// let lines = Panel::new("All tests passed")
+///     .title("Summary")
+///     .border(Border::ASCII)
+///     .render();
+///
+/// assert_eq!(lines[0].raw(), "+---- Summary -----+");
+/// assert_eq!(lines[1].raw(), "| All tests passed |");
+/// assert_eq!(lines[2].raw(), "+------------------+");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Panel {
+    content: RichString,
+    title: Option<RichString>,
+    subtitle: Option<RichString>,
+    title_alignment: Alignment,
+    padding: (usize, usize),
+    width: Option<usize>,
+    border: Border,
+    border_style: Style,
+}
+
+impl Panel {
+    /// Creates a panel around some text, which can have several lines
+    pub fn new<T: Into<RichString>>(content: T) -> Self {
+        Self {
+            content: content.into(),
+            title: None,
+            subtitle: None,
+            title_alignment: Alignment::CENTER,
+            padding: (0, 1),
+            width: None,
+            border: Border::SINGLE,
+            border_style: Style::plain(),
+        }
+    }
+
+    /// Sets the title, shown in the top border
+    pub fn title<T: Into<RichString>>(mut self, title: T) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Sets the subtitle, shown in the bottom border
+    pub fn subtitle<T: Into<RichString>>(mut self, subtitle: T) -> Self {
+        self.subtitle = Some(subtitle.into());
+        self
+    }
+
+    /// Sets the position of the title and subtitle, centered by default
+    pub fn title_alignment(mut self, alignment: Alignment) -> Self {
+        self.title_alignment = alignment;
+        self
+    }
+
+    /// Sets the number of blank lines above and below the text, and of
+    /// spaces on its left and right
+    ///
+    /// The default is no blank lines and one space.
+    pub fn padding(mut self, vertical: usize, horizontal: usize) -> Self {
+        self.padding = (vertical, horizontal);
+        self
+    }
+
+    /// Sets the width of the panel, borders included
+    ///
+    /// The text is wrapped to fit, with less padding if needed. By default,
+    /// the panel is as wide as its text.
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// Sets the characters of the border
+    pub fn border(mut self, border: Border) -> Self {
+        self.border = border;
+        self
+    }
+
+    /// Sets the style of the border
+    pub fn border_style(mut self, style: Style) -> Self {
+        self.border_style = style;
+        self
+    }
+
+    /// Renders the panel as lines
+    pub fn render(&self) -> Vec<RichString> {
+        let (vertical, horizontal) = self.padding;
+
+        // width between the left and right borders
+        let inner = match self.width {
+            Some(width) => width.saturating_sub(2),
+            None => {
+                let content = self
+                    .content
+                    .as_rich_str()
+                    .split("\n")
+                    .iter()
+                    .map(RichStr::width)
+                    .max();
+                let labels = [&self.title, &self.subtitle]
+                    .into_iter()
+                    .flatten()
+                    .map(|label| label.width() + 4)
+                    .max();
+                (content.unwrap_or(0) + 2 * horizontal).max(labels.unwrap_or(0))
+            }
+        };
+        // the horizontal padding shrinks to leave at least one column of text
+        let horizontal = horizontal.min(inner.saturating_sub(1) / 2);
+        let text_width = (inner - 2 * horizontal).max(1);
+        let text = match self.width {
+            Some(_) => self.content.as_rich_str().wrap(text_width),
+            None => self.content.as_rich_str().split("\n"),
+        };
+
+        let side = RichString::from(&self.border.vertical.to_string(), self.border_style);
+        let blank = RichString::from(&" ".repeat(inner), Style::plain());
+        let margin = RichString::from(&" ".repeat(horizontal), Style::plain());
+
+        let mut lines = vec![self.edge(
+            self.title.as_ref(),
+            inner,
+            self.border.top_left,
+            self.border.top_right,
+        )];
+        lines.extend((0..vertical).map(|_| side.clone() + blank.clone() + side.clone()));
+        lines.extend(text.iter().map(|line| {
+            let mut rendered = side.clone();
+            rendered.push(&margin);
+            rendered.push_aligned(*line, text_width, Alignment::LEFT);
+            rendered.push(&margin);
+            rendered.push(&side);
+            rendered
+        }));
+        lines.extend((0..vertical).map(|_| side.clone() + blank.clone() + side.clone()));
+        lines.push(self.edge(
+            self.subtitle.as_ref(),
+            inner,
+            self.border.bottom_left,
+            self.border.bottom_right,
+        ));
+
+        lines
+    }
+
+    /// Renders the top or bottom border, with a label
+    fn edge(
+        &self,
+        label: Option<&RichString>,
+        width: usize,
+        left: char,
+        right: char,
+    ) -> RichString {
+        let horizontal = |count: usize| {
+            RichString::from(
+                &self.border.horizontal.to_string().repeat(count),
+                self.border_style,
+            )
+        };
+
+        let label = match label {
+            Some(label) if width >= 4 => {
+                let space = RichString::from(" ", self.border_style);
+                space.clone() + label.ellipsize(width - 4) + space
+            }
+            _ => RichString::new(),
+        };
+        let remaining = width - label.width();
+        let before = match self.title_alignment {
+            _ if label.is_empty() => 0,
+            Alignment::LEFT => 1,
+            Alignment::CENTER => remaining / 2,
+            Alignment::RIGHT => remaining - 1,
+        };
+
+        RichString::from(&left.to_string(), self.border_style)
+            + horizontal(before)
+            + label
+            + horizontal(remaining - before)
+            + RichString::from(&right.to_string(), self.border_style)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shortcuts::*;
+
+    fn raw(lines: &[RichString]) -> Vec<&str> {
+        lines.iter().map(RichString::raw).collect()
+    }
+
+    #[test]
+    fn test_render() {
+        let panel = Panel::new("first line\nsecond");
+        assert_eq!(
+            raw(&panel.render()),
+            vec![
+                "┌────────────┐",
+                "│ first line │",
+                "│ second     │",
+                "└────────────┘",
+            ]
+        );
+
+        let panel = Panel::new("ok")
+            .title("Summary")
+            .subtitle("v1")
+            .title_alignment(Alignment::LEFT)
+            .padding(1, 2)
+            .border(Border::DOUBLE);
+        assert_eq!(
+            raw(&panel.render()),
+            vec![
+                "╔═ Summary ═╗",
+                "║           ║",
+                "║  ok       ║",
+                "║           ║",
+                "╚═ v1 ══════╝",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_width() {
+        let panel = Panel::new("the quick brown fox")
+            .title("a long title")
+            .title_alignment(Alignment::RIGHT)
+            .width(13)
+            .border(Border::HEAVY);
+        assert_eq!(
+            raw(&panel.render()),
+            vec![
+                "┏━ a long… ━┓",
+                "┃ the quick ┃",
+                "┃ brown fox ┃",
+                "┗━━━━━━━━━━━┛",
+            ]
+        );
+
+        let indented = Panel::new("        indented\n          ").width(6).render();
+        assert!(indented.iter().all(|line| line.width() == 6));
+
+        let narrow = Panel::new("ab").border(Border::ASCII).width(3).render();
+        assert_eq!(raw(&narrow), vec!["+-+", "|a|", "|b|", "+-+"]);
+    }
+
+    #[test]
+    fn test_styles() {
+        let lines = Panel::new(RichString::from("warning", YELLOW))
+            .title(RichString::from("CI", BOLD))
+            .border(Border::ROUNDED)
+            .border_style(BLUE)
+            .render();
+
+        assert_eq!(lines[0].raw(), "╭── CI ───╮");
+        assert_eq!(lines[0].style_at(0), BLUE);
+        assert_eq!(lines[0].style_at(3), BLUE);
+        assert_eq!(lines[0].style_at(4), BOLD);
+        assert_eq!(lines[1].style_at(0), BLUE);
+        assert_eq!(lines[1].style_at(1), Style::plain());
+        assert_eq!(lines[1].style_at(2), YELLOW);
+    }
+}
@@ -0,0 +1,317 @@
+use crate::{RichString, Style};
+use std::time::Duration;
+
+/// Block elements filling one to seven eighths of a character
+const PARTIAL_BLOCKS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// State of a task, shown by a [ProgressBar]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProgressState {
+    /// How much of the task is done, from 0 to 1
+    pub fraction: f64,
+    /// Number of items processed per second
+    pub rate: Option<f64>,
+    /// Estimated time until the task is done
+    pub eta: Option<Duration>,
+    /// Text shown after the bar
+    pub message: RichString,
+}
+
+impl ProgressState {
+    /// Creates a state with only a fraction
+    pub fn new(fraction: f64) -> Self {
+        Self {
+            fraction,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the number of items processed per second
+    pub fn rate(mut self, rate: f64) -> Self {
+        self.rate = Some(rate);
+        self
+    }
+
+    /// Sets the estimated time until the task is done
+    pub fn eta(mut self, eta: Duration) -> Self {
+        self.eta = Some(eta);
+        self
+    }
+
+    /// Sets the text shown after the bar
+    pub fn message<T: Into<RichString>>(mut self, message: T) -> Self {
+        self.message = message.into();
+        self
+    }
+}
+
+/// A progress bar
+///
+/// The bar is followed by the percentage, the rate and the ETA when they
+/// are known, and the message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgressBar {
+    width: usize,
+    fill: char,
+    fill_style: Style,
+    empty: char,
+    empty_style: Style,
+    smooth: bool,
+    info_style: Style,
+}
+
+impl Default for ProgressBar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProgressBar {
+    /// Creates a bar 30 characters wide, drawn with block elements
+    pub fn new() -> Self {
+        Self {
+            width: 30,
+            fill: '█',
+            fill_style: Style::plain(),
+            empty: '░',
+            empty_style: Style::plain(),
+            smooth: true,
+            info_style: Style::plain(),
+        }
+    }
+
+    /// Creates a bar drawn with ASCII characters, like `####----`
+    pub fn ascii() -> Self {
+        Self {
+            fill: '#',
+            empty: '-',
+            smooth: false,
+            ..Self::new()
+        }
+    }
+
+    /// Sets the width of the bar itself, in characters
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Sets the character and style of the done part
+    pub fn fill(mut self, fill: char, style: Style) -> Self {
+        self.fill = fill;
+        self.fill_style = style;
+        self
+    }
+
+    /// Sets the character and style of the part left to do
+    pub fn empty(mut self, empty: char, style: Style) -> Self {
+        self.empty = empty;
+        self.empty_style = style;
+        self
+    }
+
+    /// Sets whether the end of the done part is drawn with eighths of
+    /// characters, using block elements like `▌`
+    pub fn smooth(mut self, smooth: bool) -> Self {
+        self.smooth = smooth;
+        self
+    }
+
+    /// Sets the style of the percentage, rate and ETA
+    pub fn info_style(mut self, style: Style) -> Self {
+        self.info_style = style;
+        self
+    }
+
+    /// Renders only the bar for a fraction, from 0 to 1
+    ///
+    /// Fractions that are not finite, like NaN, are treated as 0.
+    pub fn bar(&self, fraction: f64) -> RichString {
+        let cells = clamp_fraction(fraction) * self.width as f64;
+        let mut full = cells.floor() as usize;
+        let mut partial = None;
+
+        if self.smooth {
+            match ((cells - full as f64) * 8.0).round() as usize {
+                0 => {}
+                8 => full += 1,
+                eighths => partial = Some(PARTIAL_BLOCKS[eighths - 1]),
+            }
+        }
+        let full = full.min(self.width);
+        let partial = partial.filter(|_| full < self.width);
+        let empty = self.width - full - partial.map_or(0, |_| 1);
+
+        let mut bar = RichString::from(&self.fill.to_string().repeat(full), self.fill_style);
+        if let Some(partial) = partial {
+            bar.push(&RichString::from(&partial.to_string(), self.fill_style));
+        }
+        bar.push(&RichString::from(
+            &self.empty.to_string().repeat(empty),
+            self.empty_style,
+        ));
+        bar
+    }
+
+    /// Renders the bar with the rest of the state
+    pub fn render(&self, state: &ProgressState) -> RichString {
+        let mut info = format!(" {:>3.0}%", clamp_fraction(state.fraction) * 100.0);
+        if let Some(rate) = state.rate {
+            info.push_str(&format!(" {:.1}/s", rate));
+        }
+        if let Some(eta) = state.eta {
+            info.push_str(&format!(" ETA {}", format_duration(eta)));
+        }
+
+        let mut frame = self.bar(state.fraction) + RichString::from(&info, self.info_style);
+        if !state.message.is_empty() {
+            frame.push_plain(" ");
+            frame.push(&state.message);
+        }
+        frame
+    }
+}
+
+/// Clamps a fraction between 0 and 1, with 0 for NaN and infinities
+fn clamp_fraction(fraction: f64) -> f64 {
+    if fraction.is_finite() {
+        fraction.clamp(0.0, 1.0)
+    } else {
+        0.0
+    }
+}
+
+/// Formats a duration as `m:ss` or `h:mm:ss`
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+/// An animation showing that something is happening
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Spinner {
+    frames: Vec<String>,
+    style: Style,
+}
+
+impl Spinner {
+    /// Creates a spinner from its frames
+    pub fn new<I, T>(frames: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        Self {
+            frames: frames.into_iter().map(Into::into).collect(),
+            style: Style::plain(),
+        }
+    }
+
+    /// Creates a spinner made of braille dots, like `⠋`
+    pub fn dots() -> Self {
+        Self::new(["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"])
+    }
+
+    /// Creates a spinner made of ASCII characters, like `|`
+    pub fn line() -> Self {
+        Self::new(["-", "\\", "|", "/"])
+    }
+
+    /// Sets the style of the spinner
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Renders the spinner at a step of the animation, followed by a message
+    pub fn render(&self, tick: usize, message: &RichString) -> RichString {
+        let mut frame = match self.frames.len() {
+            0 => RichString::new(),
+            count => RichString::from(&self.frames[tick % count], self.style),
+        };
+        if !message.is_empty() {
+            if !frame.is_empty() {
+                frame.push_plain(" ");
+            }
+            frame.push(message);
+        }
+        frame
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shortcuts::*;
+
+    #[test]
+    fn test_bar() {
+        let bar = ProgressBar::new().width(4);
+        assert_eq!(bar.bar(0.0).raw(), "░░░░");
+        assert_eq!(bar.bar(0.5).raw(), "██░░");
+        assert_eq!(bar.bar(0.53).raw(), "██▏░");
+        assert_eq!(bar.bar(0.6).raw(), "██▍░");
+        assert_eq!(bar.bar(0.999).raw(), "████");
+        assert_eq!(bar.bar(2.0).raw(), "████");
+        assert_eq!(bar.bar(f64::NAN).raw(), "░░░░");
+
+        let bar = ProgressBar::ascii().width(4);
+        assert_eq!(bar.bar(0.6).raw(), "##--");
+
+        let bar = ProgressBar::new()
+            .width(3)
+            .fill('=', GREEN)
+            .empty(' ', ON_BLACK);
+        assert_eq!(
+            bar.bar(0.5),
+            RichString::from("=▌", GREEN) + RichString::from(" ", ON_BLACK)
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let bar = ProgressBar::ascii().width(10).info_style(BOLD);
+
+        let state = ProgressState::new(0.25);
+        assert_eq!(bar.render(&state).raw(), "##--------  25%");
+        assert_eq!(bar.render(&state).style_at(11), BOLD);
+
+        let state = ProgressState::new(1.0)
+            .rate(12.345)
+            .eta(Duration::from_secs(3725))
+            .message(RichString::from("done", GREEN));
+        let frame = bar.render(&state);
+        assert_eq!(frame.raw(), "########## 100% 12.3/s ETA 1:02:05 done");
+        assert_eq!(frame.style_at(frame.len() - 1), GREEN);
+
+        let state = ProgressState::new(0.0).eta(Duration::from_secs(65));
+        assert_eq!(bar.render(&state).raw(), "----------   0% ETA 1:05");
+
+        for fraction in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let state = ProgressState::new(fraction);
+            assert_eq!(bar.render(&state).raw(), "----------   0%");
+        }
+    }
+
+    #[test]
+    fn test_spinner() {
+        let spinner = Spinner::line().style(CYAN);
+        let message = RichString::from("loading", Style::plain());
+
+        assert_eq!(spinner.render(0, &message).raw(), "- loading");
+        assert_eq!(spinner.render(5, &message).raw(), "\\ loading");
+        assert_eq!(spinner.render(2, &message).style_at(0), CYAN);
+        assert_eq!(Spinner::dots().render(10, &RichString::new()).raw(), "⠋");
+        assert_eq!(
+            Spinner::new(Vec::<String>::new()).render(3, &message).raw(),
+            "loading"
+        );
+        assert_eq!(Spinner::new([""]).render(0, &message).raw(), "loading");
+    }
+}
@@ -0,0 +1,232 @@
+use crate::Color;
+
+/// RGB values of the first 16 colors of the palette, as used by xterm
+const XTERM_COLORS: [(u8, u8, u8); 16] = [
+    (0x00, 0x00, 0x00),
+    (0xcd, 0x00, 0x00),
+    (0x00, 0xcd, 0x00),
+    (0xcd, 0xcd, 0x00),
+    (0x00, 0x00, 0xee),
+    (0xcd, 0x00, 0xcd),
+    (0x00, 0xcd, 0xcd),
+    (0xe5, 0xe5, 0xe5),
+    (0x7f, 0x7f, 0x7f),
+    (0xff, 0x00, 0x00),
+    (0x00, 0xff, 0x00),
+    (0xff, 0xff, 0x00),
+    (0x5c, 0x5c, 0xff),
+    (0xff, 0x00, 0xff),
+    (0x00, 0xff, 0xff),
+    (0xff, 0xff, 0xff),
+];
+
+/// Levels of each component in the 6×6×6 color cube of the palette
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl Color {
+    /// Returns the index of a named color in the palette
+    pub(crate) fn index(self) -> Option<u8> {
+        Some(match self {
+            Self::BLACK => 0,
+            Self::RED => 1,
+            Self::GREEN => 2,
+            Self::YELLOW => 3,
+            Self::BLUE => 4,
+            Self::PURPLE => 5,
+            Self::CYAN => 6,
+            Self::LIGHTGREY => 7,
+            Self::DARKGREY => 8,
+            Self::LIGHTRED => 9,
+            Self::LIGHTGREEN => 10,
+            Self::LIGHTYELLOW => 11,
+            Self::LIGHTBLUE => 12,
+            Self::LIGHTPURPLE => 13,
+            Self::LIGHTCYAN => 14,
+            Self::WHITE => 15,
+            Self::FIXED(index) => index,
+            Self::INHERIT | Self::RGB(..) => return None,
+        })
+    }
+
+    /// Returns the red, green and blue components of the color
+    ///
+    /// Named and palette colors use the default values of xterm. Returns
+    /// `None` for [Color::INHERIT], which depends on the terminal.
+    pub fn to_rgb(self) -> Option<(u8, u8, u8)> {
+        if let Self::RGB(r, g, b) = self {
+            return Some((r, g, b));
+        }
+
+        let index = self.index()?;
+        Some(match index {
+            0..=15 => XTERM_COLORS[index as usize],
+            16..=231 => {
+                let cube = index - 16;
+                (
+                    CUBE_LEVELS[(cube / 36) as usize],
+                    CUBE_LEVELS[(cube / 6 % 6) as usize],
+                    CUBE_LEVELS[(cube % 6) as usize],
+                )
+            }
+            _ => {
+                let level = 8 + 10 * (index - 232);
+                (level, level, level)
+            }
+        })
+    }
+
+    /// Mixes two colors
+    ///
+    /// `t` is the proportion of `other` in the result, from 0 to 1. The
+    /// color is returned unchanged if one of them is [Color::INHERIT].
+    pub fn mix(self, other: Self, t: f64) -> Self {
+        match (self.to_rgb(), other.to_rgb()) {
+            (Some(a), Some(b)) => {
+                let t = t.clamp(0.0, 1.0);
+                let channel = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+                Self::RGB(channel(a.0, b.0), channel(a.1, b.1), channel(a.2, b.2))
+            }
+            _ => self,
+        }
+    }
+
+    /// Makes the color lighter by mixing it with white
+    ///
+    /// `amount` goes from 0 (unchanged) to 1 (white).
+    pub fn lighten(self, amount: f64) -> Self {
+        self.mix(Self::RGB(255, 255, 255), amount)
+    }
+
+    /// Makes the color darker by mixing it with black
+    ///
+    /// `amount` goes from 0 (unchanged) to 1 (black).
+    pub fn darken(self, amount: f64) -> Self {
+        self.mix(Self::RGB(0, 0, 0), amount)
+    }
+
+    /// Returns the relative luminance of the color, as defined by WCAG
+    ///
+    /// It goes from 0 for black to 1 for white.
+    pub fn luminance(self) -> Option<f64> {
+        let (r, g, b) = self.to_rgb()?;
+
+        Some(0.2126 * to_linear(r) + 0.7152 * to_linear(g) + 0.0722 * to_linear(b))
+    }
+
+    /// Returns the contrast ratio between two colors, as defined by WCAG
+    ///
+    /// It goes from 1 for identical colors to 21 for black on white. WCAG
+    /// recommends a ratio of at least 4.5 for text.
+    pub fn contrast_ratio(self, other: Self) -> Option<f64> {
+        let a = self.luminance()?;
+        let b = other.luminance()?;
+
+        Some((a.max(b) + 0.05) / (a.min(b) + 0.05))
+    }
+
+    /// Returns black or white, whichever is the most readable on `background`
+    ///
+    /// Returns [Color::INHERIT] if the background is [Color::INHERIT].
+    pub fn best_foreground_for(background: Self) -> Self {
+        match (
+            background.contrast_ratio(Self::BLACK),
+            background.contrast_ratio(Self::WHITE),
+        ) {
+            (Some(black), Some(white)) if black >= white => Self::BLACK,
+            (Some(_), Some(_)) => Self::WHITE,
+            _ => Self::INHERIT,
+        }
+    }
+}
+
+/// Converts an sRGB component to linear light, from 0 to 1
+pub(crate) fn to_linear(channel: u8) -> f64 {
+    let c = channel as f64 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Converts linear light back to an sRGB component
+pub(crate) fn from_linear(value: f64) -> u8 {
+    let c = value.clamp(0.0, 1.0);
+    let c = if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    };
+    (c * 255.0).round() as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_rgb() {
+        assert_eq!(Color::INHERIT.to_rgb(), None);
+        assert_eq!(Color::RED.to_rgb(), Some((205, 0, 0)));
+        assert_eq!(Color::WHITE.to_rgb(), Some((255, 255, 255)));
+        assert_eq!(Color::FIXED(9).to_rgb(), Color::LIGHTRED.to_rgb());
+        assert_eq!(Color::FIXED(16).to_rgb(), Some((0, 0, 0)));
+        assert_eq!(Color::FIXED(208).to_rgb(), Some((255, 135, 0)));
+        assert_eq!(Color::FIXED(231).to_rgb(), Some((255, 255, 255)));
+        assert_eq!(Color::FIXED(232).to_rgb(), Some((8, 8, 8)));
+        assert_eq!(Color::FIXED(255).to_rgb(), Some((238, 238, 238)));
+        assert_eq!(Color::RGB(1, 2, 3).to_rgb(), Some((1, 2, 3)));
+    }
+
+    #[test]
+    fn test_mix() {
+        assert_eq!(
+            Color::RGB(0, 0, 0).mix(Color::RGB(255, 100, 10), 0.5),
+            Color::RGB(128, 50, 5)
+        );
+        assert_eq!(Color::BLUE.mix(Color::RED, 0.0), Color::RGB(0, 0, 238));
+        assert_eq!(Color::BLUE.mix(Color::RED, 2.0), Color::RGB(205, 0, 0));
+        assert_eq!(Color::BLUE.mix(Color::INHERIT, 0.5), Color::BLUE);
+        assert_eq!(Color::INHERIT.mix(Color::BLUE, 0.5), Color::INHERIT);
+    }
+
+    #[test]
+    fn test_lighten() {
+        assert_eq!(
+            Color::RGB(100, 0, 200).lighten(0.5),
+            Color::RGB(178, 128, 228)
+        );
+        assert_eq!(Color::BLACK.lighten(1.0), Color::RGB(255, 255, 255));
+    }
+
+    #[test]
+    fn test_darken() {
+        assert_eq!(Color::RGB(100, 0, 200).darken(0.5), Color::RGB(50, 0, 100));
+        assert_eq!(Color::WHITE.darken(0.0), Color::RGB(255, 255, 255));
+    }
+
+    #[test]
+    fn test_contrast_ratio() {
+        let ratio = Color::BLACK.contrast_ratio(Color::WHITE).unwrap();
+        assert!((ratio - 21.0).abs() < 1e-9);
+
+        let ratio = Color::RGB(119, 119, 119)
+            .contrast_ratio(Color::RGB(255, 255, 255))
+            .unwrap();
+        assert!((ratio - 4.48).abs() < 0.01);
+
+        assert_eq!(Color::RED.contrast_ratio(Color::RED), Some(1.0));
+        assert_eq!(Color::RED.contrast_ratio(Color::INHERIT), None);
+    }
+
+    #[test]
+    fn test_best_foreground_for() {
+        assert_eq!(Color::best_foreground_for(Color::YELLOW), Color::BLACK);
+        assert_eq!(Color::best_foreground_for(Color::BLUE), Color::WHITE);
+        assert_eq!(
+            Color::best_foreground_for(Color::RGB(40, 40, 40)),
+            Color::WHITE
+        );
+        assert_eq!(Color::best_foreground_for(Color::INHERIT), Color::INHERIT);
+    }
+}
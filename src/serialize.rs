@@ -0,0 +1,93 @@
+use crate::{RichString, Style};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A piece of text and its style, the serialized form of a span
+#[derive(Serialize, Deserialize)]
+struct Run<T> {
+    text: T,
+    style: Style,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<T>,
+}
+
+impl Serialize for RichString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let spans = self.spans_in(0, self.len());
+
+        serializer.collect_seq(self.spans().zip(spans).map(|((text, style), span)| Run {
+            text,
+            style,
+            name: span.name.as_deref(),
+        }))
+    }
+}
+
+impl<'de> Deserialize<'de> for RichString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let runs = Vec::<Run<String>>::deserialize(deserializer)?;
+
+        Ok(runs
+            .iter()
+            .map(|run| match &run.name {
+                Some(name) => RichString::named(&run.text, name).into_complemented(run.style),
+                None => RichString::from(&run.text, run.style),
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shortcuts::*;
+
+    #[test]
+    fn test_serialize() {
+        let string = RichString::from("col", RED) + "or";
+
+        assert_eq!(
+            serde_json::to_string(&string).unwrap(),
+            concat!(
+                r#"[{"text":"col","style":{"foreground":"RED","background":"INHERIT","#,
+                r#""bold":"INHERIT","italic":"INHERIT","decoration":"INHERIT","blink":"INHERIT","dim":"INHERIT","reverse":"INHERIT"}},"#,
+                r#"{"text":"or","style":{"foreground":"INHERIT","background":"INHERIT","#,
+                r#""bold":"INHERIT","italic":"INHERIT","decoration":"INHERIT","blink":"INHERIT","dim":"INHERIT","reverse":"INHERIT"}}]"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let string = RichString::from("col", RED & BOLD)
+            + RichString::from("or", ITALIC)
+            + RichString::named("ful", "keyword").into_complemented(ITALIC);
+        let json = serde_json::to_string(&string).unwrap();
+
+        assert_eq!(serde_json::from_str::<RichString>(&json).unwrap(), string);
+        assert_eq!(
+            serde_json::from_str::<RichString>("[]").unwrap(),
+            RichString::new()
+        );
+    }
+
+    #[test]
+    fn test_deserialize_missing_attributes() {
+        // written before blink, dim and reverse were added
+        let json = concat!(
+            r#"[{"text":"col","style":{"foreground":"RED","background":"INHERIT","#,
+            r#""bold":"INHERIT","italic":"INHERIT","decoration":"INHERIT"}},"#,
+            r#"{"text":"or","style":{"foreground":"INHERIT","background":"INHERIT","#,
+            r#""bold":"INHERIT","italic":"INHERIT","decoration":"INHERIT"}}]"#
+        );
+
+        assert_eq!(
+            serde_json::from_str::<RichString>(json).unwrap(),
+            RichString::from("col", RED) + "or"
+        );
+        assert_eq!(
+            serde_json::from_str::<RichString>(r#"[{"text":"a","style":{"bold":"ENABLED"}}]"#)
+                .unwrap(),
+            RichString::from("a", BOLD)
+        );
+    }
+}
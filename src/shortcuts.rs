@@ -9,6 +9,9 @@ macro_rules! foreground {
             bold: Feature::INHERIT,
             italic: Feature::INHERIT,
             decoration: Decoration::INHERIT,
+            blink: Feature::INHERIT,
+            dim: Feature::INHERIT,
+            reverse: Feature::INHERIT,
         };
     };
 }
@@ -31,6 +34,9 @@ macro_rules! background {
             bold: Feature::INHERIT,
             italic: Feature::INHERIT,
             decoration: Decoration::INHERIT,
+            blink: Feature::INHERIT,
+            dim: Feature::INHERIT,
+            reverse: Feature::INHERIT,
         };
     };
 }
@@ -51,6 +57,9 @@ pub const BOLD: Style = Style {
     bold: Feature::ENABLED,
     italic: Feature::INHERIT,
     decoration: Decoration::INHERIT,
+    blink: Feature::INHERIT,
+    dim: Feature::INHERIT,
+    reverse: Feature::INHERIT,
 };
 
 /// Enables italic
@@ -60,6 +69,9 @@ pub const ITALIC: Style = Style {
     bold: Feature::INHERIT,
     italic: Feature::ENABLED,
     decoration: Decoration::INHERIT,
+    blink: Feature::INHERIT,
+    dim: Feature::INHERIT,
+    reverse: Feature::INHERIT,
 };
 
 /// Undeline decoration
@@ -69,4 +81,7 @@ pub const UNDERLINE: Style = Style {
     bold: Feature::INHERIT,
     italic: Feature::INHERIT,
     decoration: Decoration::UNDERLINE,
+    blink: Feature::INHERIT,
+    dim: Feature::INHERIT,
+    reverse: Feature::INHERIT,
 };
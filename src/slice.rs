@@ -0,0 +1,263 @@
+use crate::iter::{Spans, StyledChars};
+use crate::string::bounds;
+use crate::{RichString, Style};
+use std::fmt;
+use std::ops::RangeBounds;
+
+/// Borrowed slice of a [RichString]
+///
+/// This is to [RichString] what `&str` is to `String`: it references the text
+/// and the style of its parent string without copying them.
+#[derive(Copy, Clone)]
+pub struct RichStr<'a> {
+    string: &'a RichString,
+    start: usize,
+    end: usize,
+}
+
+impl<'a> RichStr<'a> {
+    pub(crate) fn new(string: &'a RichString, start: usize, end: usize) -> Self {
+        Self { string, start, end }
+    }
+
+    /// Returns the parent string and the range of the slice in it
+    pub(crate) fn parts(&self) -> (&'a RichString, usize, usize) {
+        (self.string, self.start, self.end)
+    }
+
+    /// Returns the size of the slice *in characters*
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Returns `true` if the slice contains no characters
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Returns a reference to the inner text
+    pub fn raw(&self) -> &'a str {
+        &self.string.raw()[self.string.byte_offset(self.start)..self.string.byte_offset(self.end)]
+    }
+
+    /// Returns the style for a character
+    pub fn style_at(&self, index: usize) -> Style {
+        if index < self.len() {
+            self.string.style_at(self.start + index)
+        } else {
+            Style::plain()
+        }
+    }
+
+    /// Returns an iterator over the styled runs of the slice
+    pub fn spans(&self) -> Spans<'a> {
+        Spans::new(
+            self.raw(),
+            self.string.spans_in(self.start, self.end),
+            self.start,
+            self.end,
+        )
+    }
+
+    /// Returns an iterator over the characters of the slice and their style
+    pub fn styled_chars(&self) -> StyledChars<'a> {
+        StyledChars::new(
+            self.raw(),
+            self.string.spans_in(self.start, self.end),
+            self.start,
+        )
+    }
+
+    /// Returns a slice of this slice
+    ///
+    /// Works like [RichString::substring()].
+    pub fn slice(&self, start: usize, end: usize) -> Self {
+        let end = end.min(self.len());
+        let start = start.min(end);
+
+        Self::new(self.string, self.start + start, self.start + end)
+    }
+
+    /// Returns a slice of this slice from a range of characters
+    ///
+    /// Returns `None` if the range is not valid.
+    pub fn get<R: RangeBounds<usize>>(&self, range: R) -> Option<Self> {
+        let (start, end) = bounds(range, self.len())?;
+        Some(self.slice(start, end))
+    }
+
+    /// Returns the slice corresponding to a piece of the inner text
+    pub(crate) fn piece(&self, piece: &str) -> Self {
+        let start = piece.as_ptr() as usize - self.string.raw().as_ptr() as usize;
+
+        Self::new(
+            self.string,
+            self.string.char_offset(start),
+            self.string.char_offset(start + piece.len()),
+        )
+    }
+
+    /// Split the slice at `separator`
+    ///
+    /// Works like [RichString::split()] without copying the pieces.
+    pub fn split(&self, separator: &str) -> Vec<Self> {
+        self.raw().split(separator).map(|p| self.piece(p)).collect()
+    }
+
+    /// Split the slice into lines
+    ///
+    /// Works like [RichString::lines()] without copying the lines.
+    pub fn lines(&self) -> Vec<Self> {
+        self.raw().lines().map(|p| self.piece(p)).collect()
+    }
+
+    /// Copies the slice into a new [RichString]
+    pub fn to_owned(&self) -> RichString {
+        self.string.substring(self.start, self.end)
+    }
+}
+
+impl PartialEq for RichStr<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.raw() == other.raw() && self.styled_chars().eq(other.styled_chars())
+    }
+}
+
+impl Eq for RichStr<'_> {}
+
+impl PartialEq<RichString> for RichStr<'_> {
+    fn eq(&self, other: &RichString) -> bool {
+        *self == other.as_rich_str()
+    }
+}
+
+impl fmt::Debug for RichStr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.spans()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shortcuts::*;
+
+    fn colorful() -> RichString {
+        RichString::from("çōl", YELLOW)
+            + RichString::from("or", BOLD & RED)
+            + RichString::from("fûl", ITALIC & ON_PURPLE)
+    }
+
+    #[test]
+    fn test_len() {
+        let string = colorful();
+
+        assert_eq!(string.as_rich_str().len(), 8);
+        assert_eq!(string.slice(2, 5).len(), 3);
+        assert!(string.slice(5, 2).is_empty());
+    }
+
+    #[test]
+    fn test_raw() {
+        let string = colorful();
+
+        assert_eq!(string.as_rich_str().raw(), "çōlorfûl");
+        assert_eq!(string.slice(1, 7).raw(), "ōlorfû");
+        assert_eq!(string.slice(6, 20).raw(), "ûl");
+    }
+
+    #[test]
+    fn test_style_at() {
+        let slice = colorful().slice(2, 6).to_owned();
+        let slice = slice.as_rich_str();
+
+        assert_eq!(slice.style_at(0), YELLOW);
+        assert_eq!(slice.style_at(1), BOLD & RED);
+        assert_eq!(slice.style_at(3), ITALIC & ON_PURPLE);
+        assert_eq!(slice.style_at(4), Style::plain());
+    }
+
+    #[test]
+    fn test_spans() {
+        let string = colorful();
+
+        assert_eq!(
+            string.slice(1, 7).spans().collect::<Vec<_>>(),
+            vec![
+                ("ōl", YELLOW),
+                ("or", BOLD & RED),
+                ("fû", ITALIC & ON_PURPLE)
+            ]
+        );
+        assert_eq!(
+            string.slice(3, 4).spans().collect::<Vec<_>>(),
+            vec![("o", BOLD & RED)]
+        );
+        assert_eq!(string.slice(3, 3).spans().count(), 0);
+    }
+
+    #[test]
+    fn test_styled_chars() {
+        let string = colorful();
+
+        assert_eq!(
+            string.slice(2, 4).styled_chars().collect::<Vec<_>>(),
+            vec![('l', YELLOW), ('o', BOLD & RED)]
+        );
+    }
+
+    #[test]
+    fn test_slice() {
+        let string = colorful();
+
+        assert_eq!(string.slice(1, 7).slice(1, 3), string.slice(2, 4));
+        assert_eq!(string.slice(1, 7).slice(4, 10).raw(), "fû");
+    }
+
+    #[test]
+    fn test_get() {
+        let string = colorful();
+        let slice = string.slice(1, 7);
+
+        assert_eq!(slice.get(1..3), Some(string.slice(2, 4)));
+        assert_eq!(slice.get(..), Some(slice));
+        assert_eq!(slice.get(5..7), None);
+    }
+
+    #[test]
+    fn test_split() {
+        let string = RichString::from("a,b", YELLOW) + RichString::from(",c", BOLD);
+
+        assert_eq!(
+            string.as_rich_str().split(","),
+            vec![
+                RichString::from("a", YELLOW),
+                RichString::from("b", YELLOW),
+                RichString::from("c", BOLD),
+            ]
+        );
+        assert_eq!(
+            string.slice(2, 5).split(","),
+            vec![RichString::from("b", YELLOW), RichString::from("c", BOLD)]
+        );
+    }
+
+    #[test]
+    fn test_lines() {
+        let string = RichString::from("a\nb", YELLOW) + RichString::from("\nc", BOLD);
+
+        assert_eq!(
+            string.slice(2, 5).lines(),
+            vec![RichString::from("b", YELLOW), RichString::from("c", BOLD)]
+        );
+    }
+
+    #[test]
+    fn test_to_owned() {
+        let string = colorful();
+
+        assert_eq!(string.slice(2, 6).to_owned(), string.substring(2, 6));
+        let owned: RichString = string.as_rich_str().into();
+        assert_eq!(owned, string);
+    }
+}
@@ -1,12 +1,23 @@
+use crate::error::IndexError;
+use crate::index::CharIndex;
+use crate::iter::{Spans, StyledChars};
+use crate::slice::RichStr;
 use crate::style::{Style, StyleSpan};
-use crate::substring::Substring;
-use std::ops::Add;
+use crate::theme::Theme;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::mem;
+use std::ops::{Add, Bound, RangeBounds};
+use std::sync::Arc;
 
-#[derive(Debug, PartialEq)]
 /// String with syle information
+///
+/// Strings are ordered by their text first, then by the style of each character.
 pub struct RichString {
     text: String,
     style: Vec<StyleSpan>,
+    index: CharIndex,
 }
 
 impl From<&str> for RichString {
@@ -15,34 +26,65 @@ impl From<&str> for RichString {
     }
 }
 
+impl Default for RichString {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl RichString {
     /// Creates a new empty string
     pub fn new() -> Self {
-        Self {
-            text: String::new(),
-            style: vec![],
-        }
-        .into_normalised()
+        Self::from_parts(String::new(), vec![])
     }
 
     /// Creates a new string from text and a style
     ///
     /// The style will be applied to the entire string.
     pub fn from(text: &str, style: Style) -> Self {
+        let index = CharIndex::new(text);
         Self {
             text: String::from(text),
-            style: vec![StyleSpan {
-                style,
-                start: 0,
-                end: text.chars().count(),
-            }],
+            style: vec![StyleSpan::new(0, index.len(), style)],
+            index,
+        }
+        .into_normalised()
+    }
+
+    /// Creates a new string that uses a named style from a [Theme]
+    ///
+    /// The style is looked up when calling [Self::resolve()].
+    pub fn named(text: &str, name: &str) -> Self {
+        let mut string = Self::from(text, Style::plain());
+        string.set_name(name);
+        string
+    }
+
+    /// Gives a named style to the whole string, on top of its current style
+    pub(crate) fn set_name(&mut self, name: &str) {
+        let name: Arc<str> = Arc::from(name);
+        for span in self.style.iter_mut() {
+            span.name = Some(Arc::clone(&name));
+        }
+    }
+
+    fn from_parts(text: String, style: Vec<StyleSpan>) -> Self {
+        Self {
+            index: CharIndex::new(&text),
+            text,
+            style,
         }
         .into_normalised()
     }
 
     /// Returns the size of the string *in characters*
     pub fn len(&self) -> usize {
-        self.text.chars().count()
+        self.index.len()
+    }
+
+    /// Returns `true` if the string contains no characters
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
     }
 
     /// Returns a reference to the inner text
@@ -53,82 +95,155 @@ impl RichString {
     /// Returns a slice of the string
     ///
     /// Starts from index `start` to index `end` *in characters*
-    /// and keep the style as is.
+    /// and keep the style as is. Indices past the end of the string are
+    /// clamped to its length, and an empty string is returned if `start > end`.
     pub fn substring(&self, start: usize, end: usize) -> Self {
-        let mut style = Vec::new();
-
-        for span in &self.style {
-            if start >= span.start {
-                if end <= span.end {
-                    style.push(StyleSpan {
-                        style: span.style,
-                        start: 0,
-                        end: end - start,
-                    });
-
-                    break;
-                } else {
-                    style.push(StyleSpan {
-                        style: span.style,
-                        start: 0,
-                        end: span.end - start,
-                    });
-                }
-            } else {
-                if end <= span.end {
-                    style.push(StyleSpan {
-                        style: span.style,
-                        start: span.start - start,
-                        end: end - start,
-                    });
-                    break;
-                } else {
-                    style.push(StyleSpan {
-                        style: span.style,
-                        start: span.start - start,
-                        end: span.end - start,
-                    });
-                }
-            }
-        }
+        let end = end.min(self.len());
+        let start = start.min(end);
+
+        Self::from_parts(
+            String::from(
+                &self.text[self.index.byte(&self.text, start)..self.index.byte(&self.text, end)],
+            ),
+            self.spans_between(start, end),
+        )
+    }
 
-        Self {
-            text: String::from(self.text.substring(start, end)),
-            style,
+    /// Same as [Self::substring()], but returns `None` if the range is not valid
+    pub fn try_substring(&self, start: usize, end: usize) -> Option<Self> {
+        if start <= end && end <= self.len() {
+            Some(self.substring(start, end))
+        } else {
+            None
         }
-        .into_normalised()
+    }
+
+    /// Returns a slice of the string from a range of characters
+    ///
+    /// Returns `None` if the range is not valid.
+    pub fn get<R: RangeBounds<usize>>(&self, range: R) -> Option<Self> {
+        let (start, end) = bounds(range, self.len())?;
+        self.try_substring(start, end)
+    }
+
+    /// Borrows the whole string as a [RichStr]
+    pub fn as_rich_str(&self) -> RichStr<'_> {
+        RichStr::new(self, 0, self.len())
+    }
+
+    /// Borrows a slice of the string without copying it
+    ///
+    /// Works like [Self::substring()], but returns a [RichStr].
+    pub fn slice(&self, start: usize, end: usize) -> RichStr<'_> {
+        self.as_rich_str().slice(start, end)
+    }
+
+    /// Returns the slice of the string corresponding to a slice of the inner text
+    fn piece(&self, piece: &str) -> Self {
+        self.as_rich_str().piece(piece).to_owned()
+    }
+
+    /// Returns the byte offset of a character, clamped to the length of the string
+    pub(crate) fn byte_offset(&self, index: usize) -> usize {
+        self.index.byte(&self.text, index)
+    }
+
+    /// Returns the index of the character at a byte offset known to be valid
+    pub(crate) fn char_offset(&self, byte: usize) -> usize {
+        self.index.char(&self.text, byte)
+    }
+
+    /// Returns the spans overlapping a range of characters
+    pub(crate) fn spans_in(&self, start: usize, end: usize) -> &[StyleSpan] {
+        let first = self.style.partition_point(|span| span.end <= start);
+        let last = first + self.style[first..].partition_point(|span| span.start < end);
+        &self.style[first..last]
+    }
+
+    /// Returns the spans overlapping a range of characters, relative to the start of the range
+    fn spans_between(&self, start: usize, end: usize) -> Vec<StyleSpan> {
+        let first = self.style.partition_point(|span| span.end <= start);
+
+        self.style[first..]
+            .iter()
+            .take_while(|span| span.start < end)
+            .map(|span| span.with_range(span.start.max(start) - start, span.end.min(end) - start))
+            .collect()
     }
 
     /// Returns the style for a character
     pub fn style_at(&self, index: usize) -> Style {
-        for span in &self.style {
-            if index < span.end {
-                return span.style;
-            }
-        }
-        return Style::plain();
+        self.style
+            .get(self.style.partition_point(|span| span.end <= index))
+            .map_or(Style::plain(), |span| span.style)
+    }
+
+    /// Returns the name of the theme style for a character, if any
+    pub fn name_at(&self, index: usize) -> Option<&str> {
+        self.style
+            .get(self.style.partition_point(|span| span.end <= index))
+            .and_then(|span| span.name.as_deref())
+    }
+
+    /// Returns an iterator over the styled runs of the string
+    ///
+    /// Each item is a piece of text along with the style applied to it.
+    pub fn spans(&self) -> Spans<'_> {
+        Spans::new(&self.text, &self.style, 0, self.len())
+    }
+
+    /// Returns an iterator over the characters of the string and their style
+    pub fn styled_chars(&self) -> StyledChars<'_> {
+        StyledChars::new(&self.text, &self.style, 0)
     }
 
     /// Append another string
     pub fn push(&mut self, other: &Self) {
-        if other.style.len() > 0 {
-            if self.style.len() == 0 {
-                *self = other.clone();
-            } else {
-                let last = self.style.len() - 1;
-
-                for span in &other.style {
-                    self.style.push(StyleSpan {
-                        style: span.style,
-                        start: span.start + self.style[last].end,
-                        end: span.end + self.style[last].end,
-                    })
-                }
-
-                self.text.push_str(&other.text);
+        self.push_slice(other.as_rich_str());
+    }
+
+    /// Append a borrowed slice, without making a copy of it first
+    pub fn push_slice(&mut self, other: RichStr<'_>) {
+        let (parent, start, end) = other.parts();
+        let offset = self.len();
+
+        self.text.push_str(other.raw());
+        self.index.edit(&self.text, offset, 0);
+
+        let mut spans = parent.spans_in(start, end).iter().map(|span| {
+            span.with_range(
+                span.start.max(start) - start + offset,
+                span.end.min(end) - start + offset,
+            )
+        });
+
+        // merge the boundary if both sides have the same style
+        if let Some(first) = spans.next() {
+            match self.style.last_mut() {
+                Some(last) if last.end == offset && last.same_style(&first) => last.end = first.end,
+                _ => self.style.push(first),
             }
-            self.normalise();
         }
+        self.style.extend(spans);
+    }
+
+    /// Concatenates several strings
+    pub fn concat(pieces: &[Self]) -> Self {
+        pieces.iter().cloned().collect()
+    }
+
+    /// Concatenates several strings, placing `separator` between each of them
+    pub fn join(pieces: &[Self], separator: &Self) -> Self {
+        let mut joined = Self::new();
+
+        for (i, piece) in pieces.iter().enumerate() {
+            if i > 0 {
+                joined.push(separator);
+            }
+            joined.push(piece);
+        }
+
+        joined
     }
 
     /// Creates a new string without style and append it
@@ -138,27 +253,82 @@ impl RichString {
 
     /// Creates a new string with the style of the last character and append it
     pub fn push_extend(&mut self, other: &str) {
-        if self.style.len() == 0 {
-            self.push(&Self::from(other, Style::plain()));
-        } else {
-            self.push(&Self::from(other, self.style[self.style.len() - 1].style));
+        match self.style.last() {
+            Some(span) => self.push(&Self::from(other, span.style)),
+            None => self.push(&Self::from(other, Style::plain())),
         }
     }
 
     /// Insert a string at the given index
+    ///
+    /// Finding the index takes logarithmic time and only the spans around
+    /// it are merged, but the text and the spans after the index are still
+    /// moved, which is linear (it is a plain memory copy, like
+    /// [String::insert_str()]).
     pub fn insert(&mut self, index: usize, other: &Self) {
-        if other.style.len() > 0 {
-            if self.style.len() == 0 {
-                *self = other.clone();
-            } else {
-                let before = self.substring(0, index);
-                let after = self.substring(index, self.len());
-
-                *self = before;
-                self.push(other);
-                self.push(&after);
+        if other.is_empty() {
+            return;
+        }
+
+        let index = index.min(self.len());
+        let size = other.len();
+
+        self.text
+            .insert_str(self.index.byte(&self.text, index), &other.text);
+        self.index.edit(&self.text, index, 0);
+
+        let at = self.split_span(index);
+        for span in &mut self.style[at..] {
+            span.start += size;
+            span.end += size;
+        }
+        self.style.splice(
+            at..at,
+            other
+                .style
+                .iter()
+                .map(|span| span.with_range(span.start + index, span.end + index)),
+        );
+        // both strings were already normalised, only the edges can be merged
+        self.merge_spans(at, at + other.style.len());
+    }
+
+    /// Cuts the span containing a character in two, so that a span starts
+    /// at `index`, and returns the position of that span
+    fn split_span(&mut self, index: usize) -> usize {
+        let mut at = self.style.partition_point(|span| span.end <= index);
+        if let Some(span) = self.style.get_mut(at) {
+            if span.start < index {
+                let tail = span.with_range(index, span.end);
+                span.end = index;
+                at += 1;
+                self.style.insert(at, tail);
+            }
+        }
+        at
+    }
+
+    /// Same as [Self::complement()], but only for the characters in `start..end`
+    pub(crate) fn complement_range(&mut self, start: usize, end: usize, style: Style) {
+        let first = self.split_span(start);
+        let last = self.split_span(end);
+
+        for span in &mut self.style[first..last] {
+            span.style = span.style & style;
+        }
+        self.merge_spans(first, last);
+    }
+
+    /// Merges the spans with the same style around `start..end`, a range of
+    /// indices in the span vector
+    fn merge_spans(&mut self, start: usize, end: usize) {
+        let end = end.min(self.style.len().saturating_sub(1));
+
+        for i in (start.saturating_sub(1)..end).rev() {
+            if self.style[i].same_style(&self.style[i + 1]) {
+                self.style[i].end = self.style[i + 1].end;
+                self.style.remove(i + 1);
             }
-            self.normalise();
         }
     }
 
@@ -175,20 +345,120 @@ impl RichString {
     }
 
     /// Split the string at `separator`
+    ///
+    /// This behaves like [str::split()].
     pub fn split(&self, separator: &str) -> Vec<Self> {
-        let sepsize = separator.len();
-        let mut pieces = Vec::new();
-
-        let mut last = 0;
-        for i in 0..(self.len() - sepsize + 1) {
-            if self.text.substring(i, i + sepsize) == separator {
-                pieces.push(self.substring(last, i));
-                last = i + sepsize;
-            }
+        self.text.split(separator).map(|p| self.piece(p)).collect()
+    }
+
+    /// Split the string at `separator`, skipping the last piece if it is empty
+    ///
+    /// This behaves like [str::split_terminator()].
+    pub fn split_terminator(&self, separator: &str) -> Vec<Self> {
+        self.text
+            .split_terminator(separator)
+            .map(|p| self.piece(p))
+            .collect()
+    }
+
+    /// Split the string at `separator` into at most `n` pieces
+    ///
+    /// This behaves like [str::splitn()].
+    pub fn splitn(&self, n: usize, separator: &str) -> Vec<Self> {
+        self.text
+            .splitn(n, separator)
+            .map(|p| self.piece(p))
+            .collect()
+    }
+
+    /// Split the string at `separator`, starting from the end
+    ///
+    /// This behaves like [str::rsplit()].
+    pub fn rsplit(&self, separator: &str) -> Vec<Self> {
+        self.text.rsplit(separator).map(|p| self.piece(p)).collect()
+    }
+
+    /// Split the string at whitespace
+    ///
+    /// This behaves like [str::split_whitespace()].
+    pub fn split_whitespace(&self) -> Vec<Self> {
+        self.text
+            .split_whitespace()
+            .map(|p| self.piece(p))
+            .collect()
+    }
+
+    /// Split the string into lines
+    ///
+    /// This behaves like [str::lines()].
+    pub fn lines(&self) -> Vec<Self> {
+        self.text.lines().map(|p| self.piece(p)).collect()
+    }
+
+    /// Split the string in two at the given index *in characters*
+    ///
+    /// Returns `None` if the index is past the end of the string.
+    pub fn split_at(&self, index: usize) -> Option<(Self, Self)> {
+        if index > self.len() {
+            return None;
         }
-        pieces.push(self.substring(last, self.len()));
 
-        return pieces;
+        Some((self.substring(0, index), self.substring(index, self.len())))
+    }
+
+    /// Returns the byte offset of the character at `index`
+    ///
+    /// The length of the string is a valid index and maps to its length in bytes.
+    pub fn char_to_byte(&self, index: usize) -> Result<usize, IndexError> {
+        if index > self.len() {
+            Err(IndexError::OutOfBounds {
+                index,
+                len: self.len(),
+            })
+        } else {
+            Ok(self.index.byte(&self.text, index))
+        }
+    }
+
+    /// Returns the index of the character starting at byte offset `byte`
+    ///
+    /// The length of the string in bytes is a valid offset and maps to its length in characters.
+    pub fn byte_to_char(&self, byte: usize) -> Result<usize, IndexError> {
+        if byte > self.text.len() {
+            Err(IndexError::OutOfBounds {
+                index: byte,
+                len: self.text.len(),
+            })
+        } else if !self.text.is_char_boundary(byte) {
+            Err(IndexError::NotCharBoundary(byte))
+        } else {
+            Ok(self.index.char(&self.text, byte))
+        }
+    }
+
+    /// Same as [Self::substring()], but with offsets *in bytes*
+    pub fn substring_bytes(&self, start: usize, end: usize) -> Result<Self, IndexError> {
+        if start > end {
+            return Err(IndexError::InvalidRange { start, end });
+        }
+        let start_char = self.byte_to_char(start)?;
+        let end_char = self.byte_to_char(end)?;
+
+        Ok(Self::from_parts(
+            String::from(&self.text[start..end]),
+            self.spans_between(start_char, end_char),
+        ))
+    }
+
+    /// Same as [Self::style_at()], but with an offset *in bytes*
+    pub fn style_at_byte(&self, byte: usize) -> Result<Style, IndexError> {
+        Ok(self.style_at(self.byte_to_char(byte)?))
+    }
+
+    /// Same as [Self::insert()], but with an offset *in bytes*
+    pub fn insert_at_byte(&mut self, byte: usize, other: &Self) -> Result<(), IndexError> {
+        self.insert(self.byte_to_char(byte)?, other);
+        Ok(())
     }
 
     fn into_normalised(mut self) -> Self {
@@ -196,9 +466,40 @@ impl RichString {
         self
     }
 
+    /// Puts the spans in canonical form
+    ///
+    /// Spans are non-empty, cover the whole string without gaps, and two
+    /// adjacent spans never have the same style.
     fn normalise(&mut self) {
-        // remove zero-sized spans
-        self.style.retain(|span| span.start < span.end);
+        let len = self.len();
+        let mut spans: Vec<StyleSpan> = Vec::with_capacity(self.style.len());
+        let mut position = 0;
+
+        let mut push = |span: StyleSpan| match spans.last_mut() {
+            Some(last) if last.same_style(&span) => last.end = span.end,
+            _ => spans.push(span),
+        };
+
+        for span in self.style.drain(..) {
+            let start = span.start.max(position);
+            let end = span.end.min(len);
+
+            // remove zero-sized spans
+            if start >= end {
+                continue;
+            }
+            // fill gaps with plain text
+            if start > position {
+                push(StyleSpan::new(position, start, Style::plain()));
+            }
+            push(span.with_range(start, end));
+            position = end;
+        }
+        if position < len {
+            push(StyleSpan::new(position, len, Style::plain()));
+        }
+
+        self.style = spans;
     }
 
     /// See [Self::complement()].
@@ -215,6 +516,46 @@ impl RichString {
         self.normalise();
     }
 
+    /// Changes the style of each span
+    pub(crate) fn restyle<F: FnMut(Style) -> Style>(&mut self, mut f: F) {
+        for span in self.style.iter_mut() {
+            span.style = f(span.style);
+        }
+        self.normalise();
+    }
+
+    /// Changes the style of each character
+    ///
+    /// The closure gets the index of a character and its current style.
+    pub(crate) fn restyle_chars<F: FnMut(usize, Style) -> Style>(&mut self, mut f: F) {
+        for span in mem::take(&mut self.style) {
+            for index in span.start..span.end {
+                let mut piece = span.with_range(index, index + 1);
+                piece.style = f(index, span.style);
+                self.style.push(piece);
+            }
+        }
+        self.normalise();
+    }
+
+    /// See [Self::resolve()].
+    pub fn into_resolved(mut self, theme: &Theme) -> Self {
+        self.resolve(theme);
+        self
+    }
+
+    /// Replace the named styles with their value in a theme
+    ///
+    /// Styles applied directly to the string take precedence over the ones from the theme.
+    pub fn resolve(&mut self, theme: &Theme) {
+        for span in self.style.iter_mut() {
+            if let Some(name) = span.name.take() {
+                span.style = span.style & theme.get(&name);
+            }
+        }
+        self.normalise();
+    }
+
     /// See [Self::overwrite()].
     pub fn into_overwritten(mut self, style: Style) -> Self {
         self.overwrite(style);
@@ -230,6 +571,56 @@ impl RichString {
     }
 }
 
+/// Converts any range into a `start..end` range, if it fits in `len`
+pub(crate) fn bounds<R: RangeBounds<usize>>(range: R, len: usize) -> Option<(usize, usize)> {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start.checked_add(1)?,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end.checked_add(1)?,
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len,
+    };
+
+    if start <= end && end <= len {
+        Some((start, end))
+    } else {
+        None
+    }
+}
+
+impl From<RichStr<'_>> for RichString {
+    fn from(value: RichStr<'_>) -> Self {
+        value.to_owned()
+    }
+}
+
+impl FromIterator<RichString> for RichString {
+    fn from_iter<I: IntoIterator<Item = RichString>>(iter: I) -> Self {
+        let mut string = Self::new();
+        string.extend(iter);
+        string
+    }
+}
+
+impl<'a> FromIterator<(&'a str, Style)> for RichString {
+    fn from_iter<I: IntoIterator<Item = (&'a str, Style)>>(iter: I) -> Self {
+        iter.into_iter()
+            .map(|(text, style)| Self::from(text, style))
+            .collect()
+    }
+}
+
+impl Extend<RichString> for RichString {
+    fn extend<I: IntoIterator<Item = RichString>>(&mut self, iter: I) {
+        for string in iter {
+            self.push(&string);
+        }
+    }
+}
+
 impl Add<RichString> for &str {
     type Output = RichString;
 
@@ -262,10 +653,52 @@ impl Clone for RichString {
         Self {
             text: self.text.clone(),
             style: self.style.clone(),
+            index: self.index.clone(),
         }
     }
 }
 
+impl PartialEq for RichString {
+    fn eq(&self, other: &Self) -> bool {
+        self.text == other.text && self.style == other.style
+    }
+}
+
+impl Eq for RichString {}
+
+impl Hash for RichString {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.text.hash(state);
+        self.style.hash(state);
+    }
+}
+
+impl PartialOrd for RichString {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for RichString {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.text.cmp(&other.text).then_with(|| {
+            self.styled_chars()
+                .map(|(_, style)| style)
+                .cmp(other.styled_chars().map(|(_, style)| style))
+                .then_with(|| self.style.cmp(&other.style))
+        })
+    }
+}
+
+impl fmt::Debug for RichString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RichString")
+            .field("text", &self.text)
+            .field("style", &self.style)
+            .finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -335,6 +768,108 @@ mod tests {
         assert_eq!(string.style_at(8), Style::plain());
     }
 
+    #[test]
+    fn test_spans() {
+        let string = RichString::from("col", YELLOW)
+            + RichString::from("òr", BOLD & RED)
+            + RichString::from("ful", ITALIC & ON_PURPLE);
+
+        assert_eq!(
+            string.spans().collect::<Vec<_>>(),
+            vec![
+                ("col", YELLOW),
+                ("òr", BOLD & RED),
+                ("ful", ITALIC & ON_PURPLE)
+            ]
+        );
+        assert_eq!(RichString::new().spans().count(), 0);
+    }
+
+    #[test]
+    fn test_styled_chars() {
+        let string = RichString::from("cò", YELLOW) + RichString::from("l", BOLD);
+
+        assert_eq!(
+            string.styled_chars().collect::<Vec<_>>(),
+            vec![('c', YELLOW), ('ò', YELLOW), ('l', BOLD)]
+        );
+
+        for (i, (_, style)) in string.styled_chars().enumerate() {
+            assert_eq!(style, string.style_at(i));
+        }
+    }
+
+    #[test]
+    fn test_char_to_byte() {
+        let string = RichString::from("çōl", YELLOW);
+
+        assert_eq!(string.char_to_byte(0), Ok(0));
+        assert_eq!(string.char_to_byte(2), Ok(4));
+        assert_eq!(string.char_to_byte(3), Ok(5));
+        assert_eq!(
+            string.char_to_byte(4),
+            Err(IndexError::OutOfBounds { index: 4, len: 3 })
+        );
+    }
+
+    #[test]
+    fn test_byte_to_char() {
+        let string = RichString::from("çōl", YELLOW);
+
+        assert_eq!(string.byte_to_char(0), Ok(0));
+        assert_eq!(string.byte_to_char(4), Ok(2));
+        assert_eq!(string.byte_to_char(5), Ok(3));
+        assert_eq!(string.byte_to_char(1), Err(IndexError::NotCharBoundary(1)));
+        assert_eq!(
+            string.byte_to_char(6),
+            Err(IndexError::OutOfBounds { index: 6, len: 5 })
+        );
+    }
+
+    #[test]
+    fn test_substring_bytes() {
+        let string = RichString::from("çōl", YELLOW) + RichString::from("ör", BOLD);
+        let found = string.raw().find('l').unwrap();
+
+        assert_eq!(
+            string.substring_bytes(found, string.raw().len()),
+            Ok(RichString::from("l", YELLOW) + RichString::from("ör", BOLD))
+        );
+        assert_eq!(
+            string.substring_bytes(1, 4),
+            Err(IndexError::NotCharBoundary(1))
+        );
+        assert_eq!(
+            string.substring_bytes(4, 2),
+            Err(IndexError::InvalidRange { start: 4, end: 2 })
+        );
+    }
+
+    #[test]
+    fn test_style_at_byte() {
+        let string = RichString::from("çō", YELLOW) + RichString::from("l", BOLD);
+
+        assert_eq!(string.style_at_byte(2), Ok(YELLOW));
+        assert_eq!(string.style_at_byte(4), Ok(BOLD));
+        assert_eq!(string.style_at_byte(3), Err(IndexError::NotCharBoundary(3)));
+    }
+
+    #[test]
+    fn test_insert_at_byte() {
+        let mut string = RichString::from("çōful", GREEN);
+        let found = string.raw().find('f').unwrap();
+        string
+            .insert_at_byte(found, &RichString::from("lor", BOLD))
+            .unwrap();
+
+        assert_eq!(string.raw(), "çōlorful");
+        assert_eq!(string.style_at(2), BOLD);
+        assert_eq!(
+            string.insert_at_byte(1, &RichString::from("x", BOLD)),
+            Err(IndexError::NotCharBoundary(1))
+        );
+    }
+
     #[test]
     fn test_push() {
         let mut string = RichString::from("color", GREEN);
@@ -348,6 +883,109 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_push_merge() {
+        let mut string = RichString::from("col", GREEN);
+        string.push(&(RichString::from("or", GREEN) + RichString::from("ful", BOLD)));
+
+        assert_eq!(
+            string.spans().collect::<Vec<_>>(),
+            vec![("color", GREEN), ("ful", BOLD)]
+        );
+    }
+
+    #[test]
+    fn test_push_slice() {
+        let source = RichString::from("abc", BOLD) + RichString::from("def", GREEN);
+        let mut string = RichString::from("x", GREEN);
+        string.push_slice(source.slice(4, 6));
+
+        assert_eq!(string.spans().collect::<Vec<_>>(), vec![("xef", GREEN)]);
+
+        let mut string = RichString::new();
+        string.push_slice(source.slice(1, 5));
+        assert_eq!(
+            string.spans().collect::<Vec<_>>(),
+            vec![("bc", BOLD), ("de", GREEN)]
+        );
+    }
+
+    #[test]
+    fn test_from_iter() {
+        let string: RichString = vec![
+            RichString::from("col", GREEN),
+            RichString::from("or", BOLD),
+            RichString::from("ful", BOLD),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            string.spans().collect::<Vec<_>>(),
+            vec![("col", GREEN), ("orful", BOLD)]
+        );
+
+        let string: RichString = vec![("col", GREEN), ("orful", BOLD)].into_iter().collect();
+        assert_eq!(
+            string,
+            RichString::from("col", GREEN) + RichString::from("orful", BOLD)
+        );
+    }
+
+    #[test]
+    fn test_extend() {
+        let mut string = RichString::from("col", GREEN);
+        string.extend(vec![
+            RichString::from("or", BOLD),
+            RichString::from("ful", RED),
+        ]);
+
+        assert_eq!(
+            string,
+            RichString::from("col", GREEN)
+                + RichString::from("or", BOLD)
+                + RichString::from("ful", RED)
+        );
+    }
+
+    #[test]
+    fn test_concat() {
+        let pieces = [
+            RichString::from("col", GREEN),
+            RichString::from("or", GREEN),
+        ];
+
+        assert_eq!(
+            RichString::concat(&pieces),
+            RichString::from("color", GREEN)
+        );
+        assert_eq!(RichString::concat(&[]), RichString::new());
+    }
+
+    #[test]
+    fn test_join() {
+        let pieces = [
+            RichString::from("a", GREEN),
+            RichString::from("b", BOLD),
+            RichString::from("c", BOLD),
+        ];
+
+        assert_eq!(
+            RichString::join(&pieces, &RichString::from(", ", BOLD))
+                .spans()
+                .collect::<Vec<_>>(),
+            vec![("a", GREEN), (", b, c", BOLD)]
+        );
+        assert_eq!(
+            RichString::join(&pieces[..1], &RichString::from(", ", BOLD)),
+            RichString::from("a", GREEN)
+        );
+        assert_eq!(
+            RichString::join(&[], &RichString::from(", ", BOLD)),
+            RichString::new()
+        );
+    }
+
     #[test]
     fn test_push_plain() {
         let mut string = RichString::from("color", GREEN);
@@ -386,6 +1024,43 @@ mod tests {
         assert_eq!(string.style_at(7), GREEN);
     }
 
+    #[test]
+    fn test_insert_merge() {
+        let mut string = RichString::from("cful", GREEN);
+        string.insert(
+            1,
+            &(RichString::from("o", GREEN) + RichString::from("lor", BOLD)),
+        );
+        string.insert(5, &RichString::from("x", BOLD));
+        string.insert(0, &RichString::from("~", GREEN));
+        string.insert(string.len(), &RichString::from("!", GREEN));
+
+        assert_eq!(
+            string.spans().collect::<Vec<_>>(),
+            vec![("~co", GREEN), ("lorx", BOLD), ("ful!", GREEN)]
+        );
+
+        let mut empty = RichString::new();
+        empty.insert(0, &RichString::from("new", BOLD));
+        assert_eq!(empty, RichString::from("new", BOLD));
+    }
+
+    #[test]
+    fn test_insert_large() {
+        let mut string = RichString::from(&"çōłöŕfûl".repeat(100), GREEN);
+        string.insert(500, &RichString::from("ïnsërt", BOLD));
+        string.insert(10, &RichString::from("ïnsërt", RED));
+
+        assert_eq!(string.len(), 812);
+        assert_eq!(string.substring(10, 16).raw(), "ïnsërt");
+        assert_eq!(string.substring(506, 512).raw(), "ïnsërt");
+        assert_eq!(string.style_at(9), GREEN);
+        assert_eq!(string.style_at(10), RED);
+        assert_eq!(string.style_at(16), GREEN);
+        assert_eq!(string.style_at(506), BOLD);
+        assert_eq!(string.style_at(512), GREEN);
+    }
+
     #[test]
     fn test_insert_plain() {
         let mut string = RichString::from("coful", GREEN);
@@ -447,6 +1122,229 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_split_edge_cases() {
+        assert_eq!(RichString::new().split("o"), vec![RichString::new()]);
+        assert_eq!(
+            RichString::from("ab", CYAN).split(""),
+            vec![
+                RichString::new(),
+                RichString::from("a", CYAN),
+                RichString::from("b", CYAN),
+                RichString::new(),
+            ]
+        );
+        assert_eq!(
+            RichString::from("çō→ł→", CYAN).split("→"),
+            vec![
+                RichString::from("çō", CYAN),
+                RichString::from("ł", CYAN),
+                RichString::new(),
+            ]
+        );
+        assert_eq!(
+            RichString::from("aaa", CYAN).split("aa"),
+            vec![RichString::new(), RichString::from("a", CYAN)]
+        );
+    }
+
+    #[test]
+    fn test_split_terminator() {
+        let string = RichString::from("a;", CYAN) + RichString::from("b;", BOLD);
+
+        assert_eq!(
+            string.split_terminator(";"),
+            vec![RichString::from("a", CYAN), RichString::from("b", BOLD)]
+        );
+    }
+
+    #[test]
+    fn test_splitn() {
+        let string = RichString::from("a→b→c", CYAN);
+
+        assert_eq!(
+            string.splitn(2, "→"),
+            vec![RichString::from("a", CYAN), RichString::from("b→c", CYAN)]
+        );
+        assert!(string.splitn(0, "→").is_empty());
+    }
+
+    #[test]
+    fn test_rsplit() {
+        let string = RichString::from("a,", CYAN) + RichString::from("b", BOLD);
+
+        assert_eq!(
+            string.rsplit(","),
+            vec![RichString::from("b", BOLD), RichString::from("a", CYAN)]
+        );
+    }
+
+    #[test]
+    fn test_split_whitespace() {
+        let string = RichString::from(" col ", CYAN) + RichString::from("\tor  ful\n", BOLD);
+
+        assert_eq!(
+            string.split_whitespace(),
+            vec![
+                RichString::from("col", CYAN),
+                RichString::from("or", BOLD),
+                RichString::from("ful", BOLD),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lines() {
+        let string = RichString::from("col\r\n", CYAN) + RichString::from("or\n\nful\n", BOLD);
+
+        assert_eq!(
+            string.lines(),
+            vec![
+                RichString::from("col", CYAN),
+                RichString::from("or", BOLD),
+                RichString::new(),
+                RichString::from("ful", BOLD),
+            ]
+        );
+        assert!(RichString::new().lines().is_empty());
+    }
+
+    #[test]
+    fn test_split_at() {
+        let string = RichString::from("çōl", CYAN) + RichString::from("or", BOLD);
+
+        assert_eq!(
+            string.split_at(2),
+            Some((
+                RichString::from("çō", CYAN),
+                RichString::from("l", CYAN) + RichString::from("or", BOLD)
+            ))
+        );
+        assert_eq!(
+            string.split_at(5),
+            Some((string.clone(), RichString::new()))
+        );
+        assert_eq!(string.split_at(6), None);
+    }
+
+    #[test]
+    fn test_try_substring() {
+        let string = RichString::from("çōl", CYAN);
+
+        assert_eq!(
+            string.try_substring(1, 3),
+            Some(RichString::from("ōl", CYAN))
+        );
+        assert_eq!(string.try_substring(2, 1), None);
+        assert_eq!(string.try_substring(1, 4), None);
+        assert_eq!(string.substring(2, 1), RichString::new());
+        assert_eq!(string.substring(1, 4), RichString::from("ōl", CYAN));
+    }
+
+    #[test]
+    fn test_get() {
+        let string = RichString::from("çōl", CYAN);
+
+        assert_eq!(string.get(1..), Some(RichString::from("ōl", CYAN)));
+        assert_eq!(string.get(..=1), Some(RichString::from("çō", CYAN)));
+        assert_eq!(string.get(..), Some(string.clone()));
+        assert_eq!(string.get(2..5), None);
+        assert_eq!(string.get(..=usize::MAX), None);
+    }
+
+    #[test]
+    fn test_normalise() {
+        assert_eq!(
+            RichString::from("ab", RED),
+            RichString::from("a", RED) + RichString::from("b", RED)
+        );
+
+        let string = RichString::from_parts(
+            String::from("colorful"),
+            vec![
+                StyleSpan::new(1, 2, RED),
+                StyleSpan::new(2, 2, RED),
+                StyleSpan::new(2, 3, RED),
+                StyleSpan::new(4, 5, Style::plain()),
+            ],
+        );
+        assert_eq!(
+            string.style,
+            vec![
+                StyleSpan::new(0, 1, Style::plain()),
+                StyleSpan::new(1, 3, RED),
+                StyleSpan::new(3, 8, Style::plain()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_normalise_after_edit() {
+        let mut string = RichString::from("coful", GREEN);
+        string.insert(2, &RichString::from("lor", GREEN));
+        assert_eq!(string, RichString::from("colorful", GREEN));
+
+        let string = ("col" + RichString::from("orful", YELLOW)).into_complemented(YELLOW);
+        assert_eq!(string, RichString::from("colorful", YELLOW));
+    }
+
+    #[test]
+    fn test_default() {
+        assert_eq!(RichString::default(), RichString::new());
+    }
+
+    #[test]
+    fn test_hash() {
+        use std::collections::HashSet;
+
+        let mut set = HashSet::new();
+        set.insert(RichString::from("ab", RED));
+        set.insert(RichString::from("a", RED) + RichString::from("b", RED));
+        set.insert(RichString::from("ab", BLUE));
+
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn test_ord() {
+        use std::collections::BTreeSet;
+
+        let set: BTreeSet<_> = vec![
+            RichString::from("b", RED),
+            RichString::from("a", BLUE),
+            RichString::from("a", RED) + RichString::from("b", BLUE),
+            RichString::from("a", RED),
+            RichString::from("ab", RED),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            set.into_iter().collect::<Vec<_>>(),
+            vec![
+                RichString::from("a", RED),
+                RichString::from("a", BLUE),
+                RichString::from("ab", RED),
+                RichString::from("a", RED) + RichString::from("b", BLUE),
+                RichString::from("b", RED),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_named() {
+        let string = RichString::named("col", "keyword") + RichString::from("or", BOLD);
+
+        assert_eq!(string.name_at(0), Some("keyword"));
+        assert_eq!(string.name_at(3), None);
+        assert_eq!(string.style_at(0), Style::plain());
+        assert_ne!(
+            string,
+            RichString::from("col", Style::plain()) + RichString::from("or", BOLD)
+        );
+        assert_eq!(string.substring(1, 4).name_at(0), Some("keyword"));
+    }
+
     #[test]
     fn test_complement() {
         let string = "col" + RichString::from("or", PURPLE & BOLD) + "ful";
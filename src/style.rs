@@ -1,8 +1,17 @@
+use crate::error::ParseStyleError;
 use crate::{Color, Decoration, Feature};
+use std::fmt;
 use std::ops::BitAnd;
+use std::str::FromStr;
+use std::sync::Arc;
 
 /// A style to be applied on text
-#[derive(Debug, Copy, Clone, PartialEq)]
+///
+/// Styles are ordered by foreground color first, then by background color,
+/// bold, italic, decoration, blink, dim and reverse.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct Style {
     /// Text color
     pub foreground: Color,
@@ -14,21 +23,105 @@ pub struct Style {
     pub italic: Feature,
     /// Underline
     pub decoration: Decoration,
+    /// Blinking text
+    pub blink: Feature,
+    /// Faint text
+    pub dim: Feature,
+    /// Swap the text and background colors
+    pub reverse: Feature,
 }
 
 impl Style {
     /// Returns a style that inherits all its properties
-    pub fn plain() -> Self {
+    pub const fn plain() -> Self {
         Self {
             foreground: Color::INHERIT,
             background: Color::INHERIT,
             bold: Feature::INHERIT,
             italic: Feature::INHERIT,
             decoration: Decoration::INHERIT,
+            blink: Feature::INHERIT,
+            dim: Feature::INHERIT,
+            reverse: Feature::INHERIT,
         }
     }
 }
 
+impl Style {
+    /// Parses a sequence of SGR codes, like `01;38;5;208`
+    ///
+    /// These are the parameters of the escape sequences that terminals use
+    /// to change the style of the text, as found in `LS_COLORS` for example.
+    /// Codes that can't be represented by a style, like hidden text, are
+    /// ignored. Code 22 turns off both bold and dim, as it does in
+    /// terminals.
+    pub fn from_sgr(codes: &str) -> Result<Self, ParseStyleError> {
+        let mut style = Self::plain();
+        let mut codes = codes
+            .split(';')
+            .filter(|code| !code.is_empty())
+            .map(|code| code.parse::<u8>().map_err(|_| ParseStyleError::new(code)));
+
+        while let Some(code) = codes.next() {
+            match code? {
+                0 => style = Self::plain(),
+                1 => style.bold = Feature::ENABLED,
+                2 => style.dim = Feature::ENABLED,
+                3 => style.italic = Feature::ENABLED,
+                4 => style.decoration = Decoration::UNDERLINE,
+                5 | 6 => style.blink = Feature::ENABLED,
+                7 => style.reverse = Feature::ENABLED,
+                9 => style.decoration = Decoration::LINETHROUGH,
+                21 => style.bold = Feature::DISABLED,
+                22 => {
+                    style.bold = Feature::DISABLED;
+                    style.dim = Feature::DISABLED;
+                }
+                23 => style.italic = Feature::DISABLED,
+                24 | 29 => style.decoration = Decoration::NONE,
+                25 => style.blink = Feature::DISABLED,
+                27 => style.reverse = Feature::DISABLED,
+                code @ 30..=37 => style.foreground = Color::palette(code - 30),
+                code @ 40..=47 => style.background = Color::palette(code - 40),
+                code @ 90..=97 => style.foreground = Color::palette(code - 90 + 8),
+                code @ 100..=107 => style.background = Color::palette(code - 100 + 8),
+                39 => style.foreground = Color::INHERIT,
+                49 => style.background = Color::INHERIT,
+                code @ (38 | 48) => {
+                    let color = match codes.next().transpose()? {
+                        Some(5) => codes.next().transpose()?.map(Color::palette),
+                        Some(2) => match (
+                            codes.next().transpose()?,
+                            codes.next().transpose()?,
+                            codes.next().transpose()?,
+                        ) {
+                            (Some(r), Some(g), Some(b)) => Some(Color::RGB(r, g, b)),
+                            _ => None,
+                        },
+                        _ => None,
+                    }
+                    .ok_or_else(|| ParseStyleError::new(&code.to_string()))?;
+
+                    if code == 38 {
+                        style.foreground = color;
+                    } else {
+                        style.background = color;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(style)
+    }
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self::plain()
+    }
+}
+
 impl BitAnd for Style {
     type Output = Self;
 
@@ -59,20 +152,159 @@ impl BitAnd for Style {
             } else {
                 self.decoration
             },
+            blink: if self.blink == Feature::INHERIT {
+                rhs.blink
+            } else {
+                self.blink
+            },
+            dim: if self.dim == Feature::INHERIT {
+                rhs.dim
+            } else {
+                self.dim
+            },
+            reverse: if self.reverse == Feature::INHERIT {
+                rhs.reverse
+            } else {
+                self.reverse
+            },
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+impl fmt::Display for Style {
+    /// Writes the style as space-separated words, in the format accepted by [Style::from_str()]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut words = Vec::new();
+
+        if self.foreground != Color::INHERIT {
+            words.push(self.foreground.to_string());
+        }
+        if self.background != Color::INHERIT {
+            words.push(format!("on_{}", self.background));
+        }
+        match self.bold {
+            Feature::INHERIT => {}
+            Feature::ENABLED => words.push(String::from("bold")),
+            Feature::DISABLED => words.push(String::from("nobold")),
+        }
+        match self.italic {
+            Feature::INHERIT => {}
+            Feature::ENABLED => words.push(String::from("italic")),
+            Feature::DISABLED => words.push(String::from("noitalic")),
+        }
+        match self.decoration {
+            Decoration::INHERIT => {}
+            Decoration::NONE => words.push(String::from("nodecoration")),
+            Decoration::UNDERLINE => words.push(String::from("underline")),
+            Decoration::LINETHROUGH => words.push(String::from("linethrough")),
+        }
+        match self.blink {
+            Feature::INHERIT => {}
+            Feature::ENABLED => words.push(String::from("blink")),
+            Feature::DISABLED => words.push(String::from("noblink")),
+        }
+        match self.dim {
+            Feature::INHERIT => {}
+            Feature::ENABLED => words.push(String::from("dim")),
+            Feature::DISABLED => words.push(String::from("nodim")),
+        }
+        match self.reverse {
+            Feature::INHERIT => {}
+            Feature::ENABLED => words.push(String::from("reverse")),
+            Feature::DISABLED => words.push(String::from("noreverse")),
+        }
+
+        if words.is_empty() {
+            f.write_str("plain")
+        } else {
+            f.write_str(&words.join(" "))
+        }
+    }
+}
+
+impl FromStr for Style {
+    type Err = ParseStyleError;
+
+    /// Parses a style from space-separated words
+    ///
+    /// Words are colors, background colors prefixed with `on_`, `bold`,
+    /// `italic`, `underline`, `linethrough`, `blink`, `dim`, `reverse` and
+    /// their negations `nobold`, `noitalic`, `nodecoration`, `noblink`,
+    /// `nodim` and `noreverse`. `plain` is the empty style. Colors are
+    /// written as in [Color::from_str()].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut style = Self::plain();
+
+        for word in s.split_whitespace() {
+            match word {
+                "plain" => {}
+                "bold" => style.bold = Feature::ENABLED,
+                "nobold" => style.bold = Feature::DISABLED,
+                "italic" => style.italic = Feature::ENABLED,
+                "noitalic" => style.italic = Feature::DISABLED,
+                "underline" => style.decoration = Decoration::UNDERLINE,
+                "linethrough" => style.decoration = Decoration::LINETHROUGH,
+                "nodecoration" => style.decoration = Decoration::NONE,
+                "blink" => style.blink = Feature::ENABLED,
+                "noblink" => style.blink = Feature::DISABLED,
+                "dim" => style.dim = Feature::ENABLED,
+                "nodim" => style.dim = Feature::DISABLED,
+                "reverse" => style.reverse = Feature::ENABLED,
+                "noreverse" => style.reverse = Feature::DISABLED,
+                _ => match word.strip_prefix("on_") {
+                    Some(color) => {
+                        style.background = color.parse().map_err(|_| ParseStyleError::new(word))?
+                    }
+                    None => style.foreground = word.parse()?,
+                },
+            }
+        }
+
+        Ok(style)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct StyleSpan {
     pub start: usize,
     pub end: usize,
     pub style: Style,
+    /// Name of a style from a [Theme](crate::Theme), resolved later
+    ///
+    /// It is shared, so that copying spans doesn't copy the name.
+    pub name: Option<Arc<str>>,
+}
+
+impl StyleSpan {
+    pub fn new(start: usize, end: usize, style: Style) -> Self {
+        Self {
+            start,
+            end,
+            style,
+            name: None,
+        }
+    }
+
+    /// Returns the same span over another range
+    pub fn with_range(&self, start: usize, end: usize) -> Self {
+        Self {
+            start,
+            end,
+            style: self.style,
+            name: self.name.clone(),
+        }
+    }
+
+    /// Returns `true` if both spans can be merged
+    pub fn same_style(&self, other: &Self) -> bool {
+        self.style == other.style && self.name == other.name
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{ON_BLUE, RED};
 
     #[test]
     fn test_plain() {
@@ -84,8 +316,192 @@ mod tests {
                 bold: Feature::INHERIT,
                 italic: Feature::INHERIT,
                 decoration: Decoration::INHERIT,
+                blink: Feature::INHERIT,
+                dim: Feature::INHERIT,
+                reverse: Feature::INHERIT,
+            }
+        );
+    }
+
+    #[test]
+    fn test_default() {
+        assert_eq!(Style::default(), Style::plain());
+        assert_eq!(Color::default(), Color::INHERIT);
+        assert_eq!(Feature::default(), Feature::INHERIT);
+        assert_eq!(Decoration::default(), Decoration::INHERIT);
+    }
+
+    #[test]
+    fn test_ord() {
+        let plain = Style::plain();
+        let red = Style {
+            foreground: Color::RED,
+            ..plain
+        };
+        let red_bold = Style {
+            bold: Feature::ENABLED,
+            ..red
+        };
+        let blue = Style {
+            foreground: Color::BLUE,
+            ..plain
+        };
+
+        let mut styles = vec![blue, red_bold, plain, red];
+        styles.sort();
+        assert_eq!(styles, vec![plain, red, red_bold, blue]);
+    }
+
+    #[test]
+    fn test_hash() {
+        use std::collections::HashMap;
+
+        let mut cache = HashMap::new();
+        cache.insert(Style::plain(), "plain");
+        cache.insert(
+            Style {
+                foreground: Color::RED,
+                ..Style::plain()
+            },
+            "red",
+        );
+
+        assert_eq!(cache[&Style::default()], "plain");
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("".parse(), Ok(Style::plain()));
+        assert_eq!("plain".parse(), Ok(Style::plain()));
+        assert_eq!(
+            "bold lightred on_blue underline noitalic".parse(),
+            Ok(Style {
+                foreground: Color::LIGHTRED,
+                background: Color::BLUE,
+                bold: Feature::ENABLED,
+                italic: Feature::DISABLED,
+                decoration: Decoration::UNDERLINE,
+                blink: Feature::INHERIT,
+                dim: Feature::INHERIT,
+                reverse: Feature::INHERIT,
+            })
+        );
+        assert_eq!(
+            "red blue".parse(),
+            Ok(Style {
+                foreground: Color::BLUE,
+                ..Style::plain()
+            })
+        );
+        assert_eq!(
+            "bold rouge".parse::<Style>(),
+            Err(ParseStyleError::new("rouge"))
+        );
+        assert_eq!(
+            "on_rouge".parse::<Style>(),
+            Err(ParseStyleError::new("on_rouge"))
+        );
+    }
+
+    #[test]
+    fn test_from_str_extended_colors() {
+        assert_eq!(
+            "208 on_#0080ff blink dim noreverse".parse(),
+            Ok(Style {
+                foreground: Color::FIXED(208),
+                background: Color::RGB(0, 128, 255),
+                blink: Feature::ENABLED,
+                dim: Feature::ENABLED,
+                reverse: Feature::DISABLED,
+                ..Style::plain()
+            })
+        );
+        assert_eq!("1 on_4".parse(), Ok(RED & ON_BLUE));
+        assert_eq!("256".parse::<Style>(), Err(ParseStyleError::new("256")));
+        assert_eq!(
+            "#12345".parse::<Style>(),
+            Err(ParseStyleError::new("#12345"))
+        );
+        assert_eq!(
+            "#+12345".parse::<Style>(),
+            Err(ParseStyleError::new("#+12345"))
+        );
+        assert_eq!(
+            Style {
+                foreground: Color::RGB(255, 135, 0),
+                background: Color::FIXED(17),
+                blink: Feature::DISABLED,
+                ..Style::plain()
             }
+            .to_string(),
+            "#ff8700 on_17 noblink"
+        );
+
+        // the first 16 palette colors have a single written form
+        for index in 0..=255 {
+            let written = Color::FIXED(index).to_string();
+            assert_eq!(written, Color::palette(index).to_string());
+            assert_eq!(written.parse::<Color>(), Ok(Color::palette(index)));
+        }
+        assert_eq!(Color::FIXED(1).to_string(), "red");
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Style::plain().to_string(), "plain");
+
+        let style = Style {
+            foreground: Color::LIGHTRED,
+            background: Color::BLUE,
+            bold: Feature::ENABLED,
+            italic: Feature::DISABLED,
+            decoration: Decoration::LINETHROUGH,
+            blink: Feature::INHERIT,
+            dim: Feature::INHERIT,
+            reverse: Feature::INHERIT,
+        };
+        assert_eq!(
+            style.to_string(),
+            "lightred on_blue bold noitalic linethrough"
+        );
+        assert_eq!(style.to_string().parse(), Ok(style));
+    }
+
+    #[test]
+    fn test_from_sgr() {
+        assert_eq!(Style::from_sgr(""), Ok(Style::plain()));
+        assert_eq!(Style::from_sgr("00"), Ok(Style::plain()));
+        assert_eq!(
+            Style::from_sgr("01;05;37;41"),
+            Ok(Style {
+                foreground: Color::LIGHTGREY,
+                background: Color::RED,
+                bold: Feature::ENABLED,
+                blink: Feature::ENABLED,
+                ..Style::plain()
+            })
+        );
+        assert_eq!(
+            Style::from_sgr("4;38;5;208;48;2;0;128;255;7;8"),
+            Ok(Style {
+                foreground: Color::FIXED(208),
+                background: Color::RGB(0, 128, 255),
+                decoration: Decoration::UNDERLINE,
+                reverse: Feature::ENABLED,
+                ..Style::plain()
+            })
+        );
+        assert_eq!(
+            Style::from_sgr("93;104"),
+            Ok(Style {
+                foreground: Color::LIGHTYELLOW,
+                background: Color::LIGHTBLUE,
+                ..Style::plain()
+            })
         );
+        assert_eq!(Style::from_sgr("01;x"), Err(ParseStyleError::new("x")));
+        assert_eq!(Style::from_sgr("38;5"), Err(ParseStyleError::new("38")));
+        assert_eq!(Style::from_sgr("38;2;1;2"), Err(ParseStyleError::new("38")));
     }
 
     #[test]
@@ -97,12 +513,18 @@ mod tests {
                 bold: Feature::INHERIT,
                 italic: Feature::INHERIT,
                 decoration: Decoration::INHERIT,
+                blink: Feature::INHERIT,
+                dim: Feature::INHERIT,
+                reverse: Feature::INHERIT,
             } & Style {
                 foreground: Color::RED,
                 background: Color::GREEN,
                 bold: Feature::ENABLED,
                 italic: Feature::DISABLED,
                 decoration: Decoration::UNDERLINE,
+                blink: Feature::INHERIT,
+                dim: Feature::INHERIT,
+                reverse: Feature::INHERIT,
             },
             Style {
                 foreground: Color::RED,
@@ -110,6 +532,9 @@ mod tests {
                 bold: Feature::ENABLED,
                 italic: Feature::DISABLED,
                 decoration: Decoration::UNDERLINE,
+                blink: Feature::INHERIT,
+                dim: Feature::INHERIT,
+                reverse: Feature::INHERIT,
             }
         );
 
@@ -120,12 +545,18 @@ mod tests {
                 bold: Feature::DISABLED,
                 italic: Feature::ENABLED,
                 decoration: Decoration::NONE,
+                blink: Feature::INHERIT,
+                dim: Feature::INHERIT,
+                reverse: Feature::INHERIT,
             } & Style {
                 foreground: Color::RED,
                 background: Color::GREEN,
                 bold: Feature::ENABLED,
                 italic: Feature::DISABLED,
                 decoration: Decoration::UNDERLINE,
+                blink: Feature::INHERIT,
+                dim: Feature::INHERIT,
+                reverse: Feature::INHERIT,
             },
             Style {
                 foreground: Color::BLUE,
@@ -133,6 +564,9 @@ mod tests {
                 bold: Feature::DISABLED,
                 italic: Feature::ENABLED,
                 decoration: Decoration::NONE,
+                blink: Feature::INHERIT,
+                dim: Feature::INHERIT,
+                reverse: Feature::INHERIT,
             }
         );
     }
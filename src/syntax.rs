@@ -0,0 +1,149 @@
+use crate::{Highlighter, TokenKind};
+use std::ops::Range;
+use std::sync::OnceLock;
+use syntect::parsing::{ParseState, Scope, ScopeStack, SyntaxReference, SyntaxSet};
+
+/// Returns the syntaxes included with syntect, loaded once
+fn syntaxes() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+/// Highlights any language supported by syntect
+///
+/// Scopes of the grammar are mapped to [TokenKind]s, so the result is styled
+/// by the same themes as the built-in lexers.
+#[derive(Debug, Clone)]
+pub struct SyntectHighlighter {
+    syntax: &'static SyntaxReference,
+}
+
+impl SyntectHighlighter {
+    /// Finds the syntax of a file extension, like `rs`
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        syntaxes()
+            .find_syntax_by_extension(extension)
+            .map(|syntax| Self { syntax })
+    }
+
+    /// Finds a syntax by its name, like `Rust`, or by a file extension
+    pub fn from_name(name: &str) -> Option<Self> {
+        let syntaxes = syntaxes();
+        syntaxes
+            .find_syntax_by_name(name)
+            .or_else(|| syntaxes.find_syntax_by_token(name))
+            .map(|syntax| Self { syntax })
+    }
+
+    /// Returns the name of the syntax
+    pub fn name(&self) -> &str {
+        &self.syntax.name
+    }
+}
+
+/// Returns the kind of token of a scope, like `string.quoted.double`
+fn scope_kind(scope: &Scope) -> Option<TokenKind> {
+    let name = scope.build_string();
+    let is = |prefix: &str| {
+        name == prefix
+            || name
+                .strip_prefix(prefix)
+                .is_some_and(|rest| rest.starts_with('.'))
+    };
+
+    Some(if is("comment") {
+        TokenKind::COMMENT
+    } else if is("string") {
+        TokenKind::STRING
+    } else if is("constant.numeric") {
+        TokenKind::NUMBER
+    } else if is("constant") {
+        TokenKind::CONSTANT
+    } else if is("keyword.operator") {
+        TokenKind::OPERATOR
+    } else if is("keyword") || is("storage") {
+        TokenKind::KEYWORD
+    } else if is("entity.name.function") || is("support.function") {
+        TokenKind::FUNCTION
+    } else if is("variable") {
+        TokenKind::VARIABLE
+    } else if is("entity.name.tag") || is("support.type.property-name") {
+        TokenKind::KEY
+    } else if is("punctuation") {
+        TokenKind::PUNCTUATION
+    } else {
+        return None;
+    })
+}
+
+impl Highlighter for SyntectHighlighter {
+    fn tokens(&self, source: &str) -> Vec<(Range<usize>, TokenKind)> {
+        let mut tokens: Vec<(Range<usize>, TokenKind)> = Vec::new();
+        let mut state = ParseState::new(self.syntax);
+        let mut stack = ScopeStack::new();
+        let mut line_start = 0;
+
+        let mut push = |range: Range<usize>, stack: &ScopeStack| {
+            // comments and strings include their delimiters, otherwise the
+            // innermost scope with a kind wins
+            let kinds: Vec<TokenKind> = stack.as_slice().iter().filter_map(scope_kind).collect();
+            let kind = [TokenKind::COMMENT, TokenKind::STRING]
+                .into_iter()
+                .find(|kind| kinds.contains(kind))
+                .or(kinds.last().copied());
+            match (kind, tokens.last_mut()) {
+                (_, _) if range.is_empty() => {}
+                (Some(kind), Some(last)) if last.1 == kind && last.0.end == range.start => {
+                    last.0.end = range.end;
+                }
+                (Some(kind), _) => tokens.push((range, kind)),
+                (None, _) => {}
+            }
+        };
+
+        for line in source.split_inclusive('\n') {
+            let Ok(operations) = state.parse_line(line, syntaxes()) else {
+                break;
+            };
+
+            let mut position = 0;
+            for (offset, operation) in operations {
+                push(line_start + position..line_start + offset, &stack);
+                position = offset;
+                if stack.apply(&operation).is_err() {
+                    break;
+                }
+            }
+            push(line_start + position..line_start + line.len(), &stack);
+            line_start += line.len();
+        }
+
+        tokens
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens() {
+        assert!(SyntectHighlighter::from_extension("nope").is_none());
+
+        let highlighter = SyntectHighlighter::from_name("rs").unwrap();
+        assert_eq!(highlighter.name(), "Rust");
+
+        let source = "// hi\nfn main() { let x = \"s\"; }\n";
+        let tokens: Vec<(&str, TokenKind)> = highlighter
+            .tokens(source)
+            .into_iter()
+            .map(|(range, kind)| (&source[range], kind))
+            .collect();
+
+        assert!(tokens.contains(&("// hi\n", TokenKind::COMMENT)));
+        assert!(tokens.contains(&("fn", TokenKind::KEYWORD)));
+        assert!(tokens.contains(&("main", TokenKind::FUNCTION)));
+        assert!(tokens.contains(&("\"s\"", TokenKind::STRING)));
+        assert!(tokens.contains(&(";", TokenKind::PUNCTUATION)));
+    }
+}
@@ -0,0 +1,442 @@
+use crate::{Alignment, Border, RichStr, RichString, Style};
+
+/// What to do with cells that are wider than their column
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub enum Overflow {
+    /// Break the content into several lines
+    #[default]
+    WRAP,
+    /// Cut the content and end it with `…`
+    TRUNCATE,
+}
+
+/// A table of rich strings
+///
+/// ```
+/// # use perroquet::*;
+/// let lines = Table::new()
+///     .header(["name", "size"])
+///     .row(["Cargo.toml", "183"])
+///     .align(1, Alignment::RIGHT)
+///     .border(Border::ASCII)
+///     .render();
+///
+/// assert_eq!(lines[0].raw(), "+------------+------+");
+/// assert_eq!(lines[1].raw(), "| name       | size |");
+/// assert_eq!(lines[3].raw(), "| Cargo.toml |  183 |");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Table {
+    header: Option<Vec<RichString>>,
+    rows: Vec<Vec<RichString>>,
+    alignments: Vec<Alignment>,
+    max_widths: Vec<Option<usize>>,
+    width: Option<usize>,
+    overflow: Overflow,
+    header_style: Style,
+    row_styles: Vec<Style>,
+    border: Option<Border>,
+    border_style: Style,
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Table {
+    /// Creates an empty table with single line borders
+    pub fn new() -> Self {
+        Self {
+            header: None,
+            rows: Vec::new(),
+            alignments: Vec::new(),
+            max_widths: Vec::new(),
+            width: None,
+            overflow: Overflow::WRAP,
+            header_style: Style::plain(),
+            row_styles: Vec::new(),
+            border: Some(Border::SINGLE),
+            border_style: Style::plain(),
+        }
+    }
+
+    /// Sets the header of the table
+    pub fn header<I, T>(mut self, cells: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<RichString>,
+    {
+        self.header = Some(cells.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Adds a row at the end of the table
+    pub fn push_row<I, T>(&mut self, cells: I)
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<RichString>,
+    {
+        self.rows.push(cells.into_iter().map(Into::into).collect());
+    }
+
+    /// See [Self::push_row()].
+    pub fn row<I, T>(mut self, cells: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<RichString>,
+    {
+        self.push_row(cells);
+        self
+    }
+
+    /// Sets the alignment of a column, starting at 0
+    pub fn align(mut self, column: usize, alignment: Alignment) -> Self {
+        if self.alignments.len() <= column {
+            self.alignments.resize(column + 1, Alignment::LEFT);
+        }
+        self.alignments[column] = alignment;
+        self
+    }
+
+    /// Sets the maximum width of a column, starting at 0
+    ///
+    /// Columns with content are always at least one column wide.
+    pub fn max_width(mut self, column: usize, width: usize) -> Self {
+        if self.max_widths.len() <= column {
+            self.max_widths.resize(column + 1, None);
+        }
+        self.max_widths[column] = Some(width);
+        self
+    }
+
+    /// Sets the maximum width of the whole table, borders included
+    ///
+    /// The widest columns are shrunk until the table fits.
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// Sets what to do with cells that are too wide
+    pub fn overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    /// Sets the style of the header, added to the style of its cells
+    pub fn header_style(mut self, style: Style) -> Self {
+        self.header_style = style;
+        self
+    }
+
+    /// Sets styles used in turn for the rows
+    ///
+    /// Two styles with different backgrounds give zebra stripes. The
+    /// styles are added to the style of the cells.
+    pub fn row_styles<I: IntoIterator<Item = Style>>(mut self, styles: I) -> Self {
+        self.row_styles = styles.into_iter().collect();
+        self
+    }
+
+    /// Sets the characters of the borders
+    pub fn border(mut self, border: Border) -> Self {
+        self.border = Some(border);
+        self
+    }
+
+    /// Removes the borders, columns are separated by two spaces
+    pub fn borderless(mut self) -> Self {
+        self.border = None;
+        self
+    }
+
+    /// Sets the style of the borders
+    pub fn border_style(mut self, style: Style) -> Self {
+        self.border_style = style;
+        self
+    }
+
+    /// Renders the table as lines
+    pub fn render(&self) -> Vec<RichString> {
+        let widths = self.column_widths();
+        let mut lines = Vec::new();
+
+        if widths.is_empty() {
+            return lines;
+        }
+
+        if let Some(border) = self.border {
+            lines.push(self.rule(&widths, border.top_left, border.top_tee, border.top_right));
+        }
+        if let Some(header) = &self.header {
+            lines.extend(self.render_row(header, &widths, self.header_style));
+            if let Some(border) = self.border {
+                lines.push(self.rule(&widths, border.left_tee, border.cross, border.right_tee));
+            }
+        }
+        for (i, row) in self.rows.iter().enumerate() {
+            let style = match self.row_styles.len() {
+                0 => Style::plain(),
+                count => self.row_styles[i % count],
+            };
+            lines.extend(self.render_row(row, &widths, style));
+        }
+        if let Some(border) = self.border {
+            lines.push(self.rule(
+                &widths,
+                border.bottom_left,
+                border.bottom_tee,
+                border.bottom_right,
+            ));
+        }
+
+        lines
+    }
+
+    /// Computes the width of each column, without padding
+    fn column_widths(&self) -> Vec<usize> {
+        let mut widths: Vec<usize> = Vec::new();
+
+        for row in self.header.iter().chain(self.rows.iter()) {
+            if widths.len() < row.len() {
+                widths.resize(row.len(), 0);
+            }
+            for (width, cell) in widths.iter_mut().zip(row) {
+                let cell_width = cell
+                    .as_rich_str()
+                    .split("\n")
+                    .iter()
+                    .map(RichStr::width)
+                    .max();
+                *width = (*width).max(cell_width.unwrap_or(0));
+            }
+        }
+
+        for (width, max) in widths.iter_mut().zip(&self.max_widths) {
+            if let Some(max) = max {
+                *width = (*width).min((*max).max(1));
+            }
+        }
+
+        if let Some(limit) = self.width {
+            let decoration = match self.border {
+                Some(_) => 3 * widths.len() + 1,
+                None => 2 * widths.len().saturating_sub(1),
+            };
+            let mut total: usize = widths.iter().sum::<usize>() + decoration;
+
+            while total > limit {
+                match widths.iter_mut().filter(|width| **width > 1).max() {
+                    Some(widest) => *widest -= 1,
+                    None => break,
+                }
+                total -= 1;
+            }
+        }
+
+        widths
+    }
+
+    /// Renders a horizontal line of the border
+    fn rule(&self, widths: &[usize], left: char, middle: char, right: char) -> RichString {
+        let horizontal = self.border.unwrap_or_default().horizontal;
+        let mut line = String::from(left);
+
+        for (i, width) in widths.iter().enumerate() {
+            if i > 0 {
+                line.push(middle);
+            }
+            line.push_str(&horizontal.to_string().repeat(width + 2));
+        }
+        line.push(right);
+
+        RichString::from(&line, self.border_style)
+    }
+
+    /// Renders the lines of a row
+    fn render_row(&self, row: &[RichString], widths: &[usize], style: Style) -> Vec<RichString> {
+        let cells: Vec<Vec<RichStr>> = widths
+            .iter()
+            .enumerate()
+            .map(|(i, width)| match row.get(i) {
+                Some(cell) => self.cell_lines(cell, *width),
+                None => Vec::new(),
+            })
+            .collect();
+        let height = cells.iter().map(Vec::len).max().unwrap_or(0).max(1);
+        let vertical = self
+            .border
+            .map(|border| RichString::from(&border.vertical.to_string(), self.border_style));
+
+        (0..height)
+            .map(|line| {
+                let mut rendered = RichString::new();
+
+                for (i, (cell, width)) in cells.iter().zip(widths).enumerate() {
+                    let alignment = self.alignments.get(i).copied().unwrap_or_default();
+                    let (before, after) = match &vertical {
+                        Some(vertical) => {
+                            rendered.push(vertical);
+                            (" ", " ")
+                        }
+                        None if i > 0 => ("  ", ""),
+                        None => ("", ""),
+                    };
+
+                    let start = rendered.len();
+                    rendered.push_plain(before);
+                    match cell.get(line) {
+                        Some(content) => self.push_cell(&mut rendered, *content, *width, alignment),
+                        None => rendered.push_plain(&" ".repeat(*width)),
+                    }
+                    rendered.push_plain(after);
+                    rendered.complement_range(start, rendered.len(), style);
+                }
+                if let Some(vertical) = &vertical {
+                    rendered.push(vertical);
+                }
+
+                rendered
+            })
+            .collect()
+    }
+
+    /// Splits the content of a cell into lines
+    ///
+    /// With [Overflow::TRUNCATE], the lines are cut by [Self::push_cell()].
+    fn cell_lines<'a>(&self, cell: &'a RichString, width: usize) -> Vec<RichStr<'a>> {
+        match self.overflow {
+            Overflow::WRAP => cell.as_rich_str().wrap(width),
+            Overflow::TRUNCATE => cell.as_rich_str().split("\n"),
+        }
+    }
+
+    /// Appends a line of a cell, fitted to the width of its column
+    fn push_cell(
+        &self,
+        rendered: &mut RichString,
+        line: RichStr,
+        width: usize,
+        alignment: Alignment,
+    ) {
+        match self.overflow {
+            Overflow::WRAP => rendered.push_aligned(line, width, alignment),
+            Overflow::TRUNCATE => {
+                let used = line.ellipsized_width(width);
+                rendered.push_padded(width, used, alignment, |rendered| {
+                    rendered.push_ellipsized(line, width)
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shortcuts::*;
+
+    fn raw(lines: &[RichString]) -> Vec<&str> {
+        lines.iter().map(RichString::raw).collect()
+    }
+
+    #[test]
+    fn test_render() {
+        let table = Table::new()
+            .header(["name", "size"])
+            .row([
+                RichString::from("src", BLUE),
+                RichString::from("12", Style::plain()),
+            ])
+            .row(["Cargo.toml", "1823"])
+            .align(1, Alignment::RIGHT);
+
+        assert_eq!(
+            raw(&table.render()),
+            vec![
+                "┌────────────┬──────┐",
+                "│ name       │ size │",
+                "├────────────┼──────┤",
+                "│ src        │   12 │",
+                "│ Cargo.toml │ 1823 │",
+                "└────────────┴──────┘",
+            ]
+        );
+        assert_eq!(table.render()[3].style_at(2), BLUE);
+        assert_eq!(table.render()[3].style_at(6), Style::plain());
+
+        assert!(Table::new().render().is_empty());
+    }
+
+    #[test]
+    fn test_borderless() {
+        let table = Table::new()
+            .row(["a", "b", "c"])
+            .row(["dd", "", "e"])
+            .align(1, Alignment::CENTER)
+            .borderless();
+
+        assert_eq!(raw(&table.render()), vec!["a   b  c", "dd     e"]);
+    }
+
+    #[test]
+    fn test_overflow() {
+        let table = Table::new()
+            .row(["a", "the quick brown fox"])
+            .max_width(1, 9)
+            .border(Border::ASCII);
+
+        assert_eq!(
+            raw(&table.render()),
+            vec![
+                "+---+-----------+",
+                "| a | the quick |",
+                "|   | brown fox |",
+                "+---+-----------+",
+            ]
+        );
+
+        let truncated = table.overflow(Overflow::TRUNCATE).width(14).render();
+        assert_eq!(
+            raw(&truncated),
+            vec!["+---+--------+", "| a | the q… |", "+---+--------+",]
+        );
+
+        let narrow = Table::new()
+            .row(["ab", ""])
+            .max_width(0, 0)
+            .max_width(1, 0)
+            .border(Border::ASCII);
+        assert_eq!(
+            raw(&narrow.render()),
+            vec!["+---+--+", "| a |  |", "| b |  |", "+---+--+"]
+        );
+        assert_eq!(
+            raw(&narrow.overflow(Overflow::TRUNCATE).render()),
+            vec!["+---+--+", "| … |  |", "+---+--+"]
+        );
+    }
+
+    #[test]
+    fn test_styles() {
+        let lines = Table::new()
+            .header(["name"])
+            .row([RichString::from("a", BOLD)])
+            .row(["b"])
+            .row(["c"])
+            .header_style(UNDERLINE)
+            .row_styles([ON_BLACK, ON_BLUE])
+            .border_style(PURPLE)
+            .render();
+
+        assert_eq!(lines[0].style_at(0), PURPLE);
+        assert_eq!(lines[1].style_at(0), PURPLE);
+        assert_eq!(lines[1].style_at(2), UNDERLINE);
+        assert_eq!(lines[3].style_at(1), ON_BLACK);
+        assert_eq!(lines[3].style_at(2), BOLD & ON_BLACK);
+        assert_eq!(lines[4].style_at(2), ON_BLUE);
+        assert_eq!(lines[5].style_at(3), ON_BLACK);
+    }
+}
@@ -0,0 +1,224 @@
+use crate::{Alignment, RichString, Style};
+
+/// Several lines of rich text
+///
+/// Blocks of text can be stacked with [Self::push_below()] or placed side
+/// by side with [Self::push_beside()], which makes it the building block
+/// of layouts.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct RichText {
+    lines: Vec<RichString>,
+}
+
+impl RichText {
+    /// Creates a new text without any line
+    pub fn new() -> Self {
+        Self { lines: Vec::new() }
+    }
+
+    /// Returns the lines of the text
+    pub fn lines(&self) -> &[RichString] {
+        &self.lines
+    }
+
+    /// Returns the lines of the text
+    pub fn into_lines(self) -> Vec<RichString> {
+        self.lines
+    }
+
+    /// Returns the number of columns taken by the widest line
+    pub fn width(&self) -> usize {
+        self.lines.iter().map(RichString::width).max().unwrap_or(0)
+    }
+
+    /// Returns the number of lines
+    pub fn height(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Adds lines at the end of the text
+    ///
+    /// The string is split on newlines.
+    pub fn push_line<T: Into<RichString>>(&mut self, line: T) {
+        self.lines.extend(line.into().split("\n"));
+    }
+
+    /// Adds another text below this one
+    pub fn push_below(&mut self, other: &Self) {
+        self.lines.extend(other.lines.iter().cloned());
+    }
+
+    /// See [Self::push_below()].
+    pub fn below(mut self, other: &Self) -> Self {
+        self.push_below(other);
+        self
+    }
+
+    /// Places another text on the right of this one
+    ///
+    /// The lines of this text are padded to the same width so that the
+    /// other text is aligned, and the shorter text gets empty lines.
+    pub fn push_beside(&mut self, other: &Self) {
+        let width = self.width();
+
+        if self.lines.len() < other.lines.len() {
+            self.lines.resize(other.lines.len(), RichString::new());
+        }
+        for (i, line) in self.lines.iter_mut().enumerate() {
+            let mut padded = line.align(width, Alignment::LEFT);
+            if let Some(right) = other.lines.get(i) {
+                padded.push(right);
+            }
+            *line = padded;
+        }
+    }
+
+    /// See [Self::push_beside()].
+    pub fn beside(mut self, other: &Self) -> Self {
+        self.push_beside(other);
+        self
+    }
+
+    /// Pads every line with spaces to fill a number of columns
+    pub fn align(&self, width: usize, alignment: Alignment) -> Self {
+        self.lines
+            .iter()
+            .map(|line| line.align(width, alignment))
+            .collect()
+    }
+
+    /// Adds empty lines at the end until the text has a number of lines
+    pub fn pad_height(&mut self, height: usize) {
+        if self.lines.len() < height {
+            self.lines.resize(height, RichString::new());
+        }
+    }
+
+    /// Breaks every line to fit in a number of columns
+    ///
+    /// See [RichString::wrap()].
+    pub fn wrap(&self, width: usize) -> Self {
+        self.lines
+            .iter()
+            .flat_map(|line| line.wrap(width))
+            .collect()
+    }
+
+    /// Add a style to every line without overriding already present styles
+    pub fn complement(&mut self, style: Style) {
+        for line in self.lines.iter_mut() {
+            line.complement(style);
+        }
+    }
+}
+
+impl From<RichString> for RichText {
+    fn from(value: RichString) -> Self {
+        Self {
+            lines: value.split("\n"),
+        }
+    }
+}
+
+impl From<&str> for RichText {
+    fn from(value: &str) -> Self {
+        RichString::from(value, Style::plain()).into()
+    }
+}
+
+impl From<Vec<RichString>> for RichText {
+    fn from(lines: Vec<RichString>) -> Self {
+        Self { lines }
+    }
+}
+
+/// Joins the lines with plain newlines
+impl From<RichText> for RichString {
+    fn from(value: RichText) -> Self {
+        Self::join(&value.lines, &Self::from("\n", Style::plain()))
+    }
+}
+
+impl FromIterator<RichString> for RichText {
+    fn from_iter<I: IntoIterator<Item = RichString>>(iter: I) -> Self {
+        Self {
+            lines: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shortcuts::*;
+
+    fn raw(text: &RichText) -> Vec<&str> {
+        text.lines().iter().map(RichString::raw).collect()
+    }
+
+    #[test]
+    fn test_from() {
+        let string = RichString::from("first\nsec", RED) + RichString::from("ond\n", BLUE);
+        let text = RichText::from(string.clone());
+
+        assert_eq!(raw(&text), vec!["first", "second", ""]);
+        assert_eq!(
+            text.lines()[1],
+            RichString::from("sec", RED) + RichString::from("ond", BLUE)
+        );
+        let joined: RichString = text.into();
+        assert_eq!(joined.raw(), string.raw());
+        assert_eq!(joined.style_at(5), Style::plain());
+        assert_eq!(joined.style_at(8), RED);
+        let empty: RichString = RichText::new().into();
+        assert_eq!(empty, RichString::new());
+    }
+
+    #[test]
+    fn test_size() {
+        let text = RichText::from("a\n日本語\nabcd");
+        assert_eq!(text.width(), 6);
+        assert_eq!(text.height(), 3);
+        assert_eq!(RichText::new().width(), 0);
+        assert_eq!(RichText::new().height(), 0);
+    }
+
+    #[test]
+    fn test_push_line() {
+        let mut text = RichText::new();
+        text.push_line("a");
+        text.push_line(RichString::from("b\nc", RED));
+        assert_eq!(raw(&text), vec!["a", "b", "c"]);
+        assert_eq!(text.lines()[2], RichString::from("c", RED));
+    }
+
+    #[test]
+    fn test_below() {
+        let text = RichText::from("a\nb").below(&RichText::from("c"));
+        assert_eq!(raw(&text), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_beside() {
+        let left = RichText::from(RichString::from("ab\nc", RED));
+        let right = RichText::from("1\n2\n3");
+
+        let text = left.clone().beside(&right);
+        assert_eq!(raw(&text), vec!["ab1", "c 2", "  3"]);
+        assert_eq!(text.lines()[1].style_at(0), RED);
+        assert_eq!(text.lines()[1].style_at(1), Style::plain());
+
+        let text = right.beside(&left);
+        assert_eq!(raw(&text), vec!["1ab", "2c", "3"]);
+    }
+
+    #[test]
+    fn test_wrap() {
+        let text = RichText::from("one two\nthree").wrap(3);
+        assert_eq!(raw(&text), vec!["one", "two", "thr", "ee"]);
+        assert_eq!(
+            raw(&text.align(4, Alignment::RIGHT)),
+            vec![" one", " two", " thr", "  ee"]
+        );
+    }
+}
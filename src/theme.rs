@@ -0,0 +1,261 @@
+use crate::error::ThemeError;
+use crate::Style;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+/// A set of named styles
+///
+/// Names are hierarchical: an entry called `error.path` inherits every
+/// property it doesn't set itself from the `error` entry.
+///
+/// Strings created with [RichString::named()](crate::RichString::named)
+/// reference a style by its name, and get their actual style from a theme
+/// with [RichString::resolve()](crate::RichString::resolve).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Theme {
+    styles: BTreeMap<String, Style>,
+}
+
+impl Theme {
+    /// Creates a new empty theme
+    pub fn new() -> Self {
+        Self {
+            styles: BTreeMap::new(),
+        }
+    }
+
+    /// Adds or replaces a named style
+    pub fn set(&mut self, name: &str, style: Style) {
+        self.styles.insert(String::from(name), style);
+    }
+
+    /// See [Self::set()].
+    pub fn with(mut self, name: &str, style: Style) -> Self {
+        self.set(name, style);
+        self
+    }
+
+    /// Returns `true` if the theme has an entry with that exact name
+    pub fn contains(&self, name: &str) -> bool {
+        self.styles.contains_key(name)
+    }
+
+    /// Returns the style for a name, including inherited properties
+    ///
+    /// Unknown names get the style of their closest parent, or a plain style.
+    pub fn get(&self, name: &str) -> Style {
+        let mut style = Style::plain();
+        let mut name = Some(name);
+
+        while let Some(current) = name {
+            if let Some(entry) = self.styles.get(current) {
+                style = style & *entry;
+            }
+            name = current.rsplit_once('.').map(|(parent, _)| parent);
+        }
+
+        style
+    }
+
+    /// Changes every style of the theme
+    pub(crate) fn restyle<F: FnMut(Style) -> Style>(&mut self, mut f: F) {
+        for style in self.styles.values_mut() {
+            *style = f(*style);
+        }
+    }
+
+    /// Parses a theme file
+    ///
+    /// The format is a small subset of TOML/INI. Each entry is a name and a
+    /// style in the format of [Style::from_str()], quoted or not, and the
+    /// names in a `[section]` are prefixed with the name of the section.
+    /// Lines starting with `#` or `;` are comments.
+    ///
+    /// ```text
+    /// keyword = "bold purple"
+    ///
+    /// [error]
+    /// path = underline   # same as error.path
+    /// ```
+    pub fn parse(source: &str) -> Result<Self, ThemeError> {
+        let mut theme = Self::new();
+        let mut section = String::new();
+
+        for (number, line) in source.lines().enumerate() {
+            let line = line.trim();
+            let number = number + 1;
+
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+
+            if let Some(header) = line.strip_prefix('[') {
+                let header = strip_comment(header)
+                    .strip_suffix(']')
+                    .map(str::trim)
+                    .filter(|header| !header.is_empty())
+                    .ok_or(ThemeError::Syntax { line: number })?;
+                section = String::from(header);
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or(ThemeError::Syntax { line: number })?;
+            let key = key.trim();
+            let value =
+                unquote(strip_comment(value.trim())).ok_or(ThemeError::Syntax { line: number })?;
+            if key.is_empty() {
+                return Err(ThemeError::Syntax { line: number });
+            }
+
+            let style = value.parse().map_err(|error| ThemeError::Style {
+                line: number,
+                error,
+            })?;
+            if section.is_empty() {
+                theme.set(key, style);
+            } else {
+                theme.set(&format!("{}.{}", section, key), style);
+            }
+        }
+
+        Ok(theme)
+    }
+
+    /// Reads and parses a theme file
+    ///
+    /// See [Self::parse()] for the format.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ThemeError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+}
+
+impl FromStr for Theme {
+    type Err = ThemeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Removes a trailing comment, unless it is inside quotes
+fn strip_comment(value: &str) -> &str {
+    let mut quote = None;
+
+    for (i, c) in value.char_indices() {
+        match (c, quote) {
+            ('"' | '\'', None) => quote = Some(c),
+            (_, Some(q)) if c == q => quote = None,
+            ('#', None) => return value[..i].trim_end(),
+            _ => {}
+        }
+    }
+
+    value
+}
+
+/// Removes the quotes around a value, if any
+fn unquote(value: &str) -> Option<&str> {
+    for quote in ['"', '\''] {
+        if let Some(inner) = value.strip_prefix(quote) {
+            return inner.strip_suffix(quote);
+        }
+    }
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shortcuts::*;
+    use crate::RichString;
+
+    #[test]
+    fn test_get() {
+        let theme = Theme::new()
+            .with("error", RED & BOLD)
+            .with("error.path", UNDERLINE & BLUE);
+
+        assert_eq!(theme.get("error"), RED & BOLD);
+        assert_eq!(theme.get("error.path"), BLUE & BOLD & UNDERLINE);
+        assert_eq!(theme.get("error.path.line"), BLUE & BOLD & UNDERLINE);
+        assert_eq!(theme.get("warning"), Style::plain());
+        assert!(theme.contains("error.path"));
+        assert!(!theme.contains("error.path.line"));
+    }
+
+    #[test]
+    fn test_parse() {
+        let theme = Theme::parse(
+            "# comment\n\
+             keyword = \"bold purple\"\n\
+             \n\
+             [error]\n\
+             path = underline   # comment\n\
+             ; other comment\n\
+             [ warning ]\n\
+             name = 'yellow on_black'\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            theme,
+            Theme::new()
+                .with("keyword", BOLD & PURPLE)
+                .with("error.path", UNDERLINE)
+                .with("warning.name", YELLOW & ON_BLACK)
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(
+            Theme::parse("error = red\nwarning"),
+            Err(ThemeError::Syntax { line: 2 })
+        ));
+        assert!(matches!(
+            Theme::parse("[error"),
+            Err(ThemeError::Syntax { line: 1 })
+        ));
+        assert!(matches!(
+            Theme::parse("error = \"red"),
+            Err(ThemeError::Syntax { line: 1 })
+        ));
+        match Theme::parse("\nerror = bold rouge") {
+            Err(ThemeError::Style { line, error }) => {
+                assert_eq!(line, 2);
+                assert_eq!(error.word(), "rouge");
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_load() {
+        assert!(matches!(
+            Theme::load("/this/file/does/not/exist.toml"),
+            Err(ThemeError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_resolve() {
+        let theme = Theme::new()
+            .with("error", RED & BOLD)
+            .with("path", UNDERLINE);
+
+        let string = RichString::named("error: ", "error")
+            + RichString::named("main.rs", "path").into_complemented(BLUE)
+            + " not found";
+
+        assert_eq!(
+            string.into_resolved(&theme),
+            RichString::from("error: ", RED & BOLD)
+                + RichString::from("main.rs", BLUE & UNDERLINE)
+                + " not found"
+        );
+    }
+}
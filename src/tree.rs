@@ -0,0 +1,241 @@
+use crate::{RichString, RichText, Style};
+
+/// Characters used to draw the guides of a tree
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Guides {
+    /// Before a node that has siblings after it
+    pub branch: &'static str,
+    /// Before the last node of a level
+    pub last: &'static str,
+    /// Below a node that has siblings after it
+    pub vertical: &'static str,
+    /// Below the last node of a level
+    pub space: &'static str,
+    /// Style of the guides
+    pub style: Style,
+}
+
+impl Guides {
+    /// Box-drawing characters, like `├──`
+    pub const UNICODE: Self = Self {
+        branch: "├── ",
+        last: "└── ",
+        vertical: "│   ",
+        space: "    ",
+        style: Style::plain(),
+    };
+
+    /// ASCII characters only, like `|--`
+    pub const ASCII: Self = Self {
+        branch: "|-- ",
+        last: "`-- ",
+        vertical: "|   ",
+        space: "    ",
+        style: Style::plain(),
+    };
+
+    /// Returns the same guides with another style
+    pub fn with_style(self, style: Style) -> Self {
+        Self { style, ..self }
+    }
+}
+
+impl Default for Guides {
+    fn default() -> Self {
+        Self::UNICODE
+    }
+}
+
+/// Nested nodes with rich labels
+///
+/// ```
+/// # use perroquet::*;
+/// let tree = Tree::new("perroquet")
+///     .child(Tree::new("serde").leaf("serde_derive"))
+///     .leaf("unicode-width");
+///
+/// let text = tree.render();
+/// let lines: Vec<&str> = text.lines().iter().map(RichString::raw).collect();
+/// assert_eq!(
+///     lines,
+///     [
+///         "perroquet",
+///         "├── serde",
+///         "│   └── serde_derive",
+///         "└── unicode-width",
+///     ]
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tree {
+    label: RichText,
+    children: Vec<Tree>,
+}
+
+impl Tree {
+    /// Creates a node without children
+    ///
+    /// The label can have several lines.
+    pub fn new<T: Into<RichText>>(label: T) -> Self {
+        Self {
+            label: label.into(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Adds a child at the end
+    pub fn push(&mut self, child: Self) {
+        self.children.push(child);
+    }
+
+    /// See [Self::push()].
+    pub fn child(mut self, child: Self) -> Self {
+        self.push(child);
+        self
+    }
+
+    /// Adds a child without children
+    pub fn leaf<T: Into<RichText>>(self, label: T) -> Self {
+        self.child(Self::new(label))
+    }
+
+    /// Returns the children of the node
+    pub fn children(&self) -> &[Self] {
+        &self.children
+    }
+
+    /// Renders the tree with [Guides::UNICODE]
+    pub fn render(&self) -> RichText {
+        self.render_with(&Guides::UNICODE)
+    }
+
+    /// Renders the tree with some guides
+    pub fn render_with(&self, guides: &Guides) -> RichText {
+        let mut text = RichText::new();
+        let guide = |guide: &str| RichString::from(guide, guides.style);
+
+        self.render_node(
+            &mut text,
+            guides,
+            &RichString::new(),
+            &RichString::new(),
+            &guide,
+        );
+        text
+    }
+
+    /// Renders a node and its children
+    ///
+    /// `first` goes before the first line of the label, and `prefix`
+    /// before everything under it.
+    fn render_node<F: Fn(&str) -> RichString>(
+        &self,
+        text: &mut RichText,
+        guides: &Guides,
+        first: &RichString,
+        prefix: &RichString,
+        guide: &F,
+    ) {
+        // the other lines of the label line up with the first one
+        for (i, line) in self.label.lines().iter().enumerate() {
+            if i == 0 {
+                text.push_line(first.clone() + line.clone());
+            } else {
+                text.push_line(prefix.clone() + line.clone());
+            }
+        }
+        if self.label.height() == 0 {
+            text.push_line(first.clone());
+        }
+
+        for (i, child) in self.children.iter().enumerate() {
+            let (branch, below) = if i + 1 == self.children.len() {
+                (guides.last, guides.space)
+            } else {
+                (guides.branch, guides.vertical)
+            };
+            child.render_node(
+                text,
+                guides,
+                &(prefix.clone() + guide(branch)),
+                &(prefix.clone() + guide(below)),
+                guide,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shortcuts::*;
+
+    fn raw(text: &RichText) -> Vec<&str> {
+        text.lines().iter().map(RichString::raw).collect()
+    }
+
+    #[test]
+    fn test_render() {
+        let tree = Tree::new("src")
+            .child(Tree::new("bin").leaf("main.rs").leaf("cli.rs"))
+            .child(Tree::new("lib").leaf("mod.rs"))
+            .leaf("lib.rs");
+
+        assert_eq!(
+            raw(&tree.render()),
+            vec![
+                "src",
+                "├── bin",
+                "│   ├── main.rs",
+                "│   └── cli.rs",
+                "├── lib",
+                "│   └── mod.rs",
+                "└── lib.rs",
+            ]
+        );
+        assert_eq!(
+            raw(&tree.render_with(&Guides::ASCII)),
+            vec![
+                "src",
+                "|-- bin",
+                "|   |-- main.rs",
+                "|   `-- cli.rs",
+                "|-- lib",
+                "|   `-- mod.rs",
+                "`-- lib.rs",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_multi_line_labels() {
+        let tree = Tree::new("root\nline")
+            .child(Tree::new("a\nb").leaf("c"))
+            .leaf("d\ne");
+
+        assert_eq!(
+            raw(&tree.render()),
+            vec![
+                "root",
+                "line",
+                "├── a",
+                "│   b",
+                "│   └── c",
+                "└── d",
+                "    e",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_styles() {
+        let tree = Tree::new(RichString::from("root", BOLD)).leaf(RichString::from("leaf", RED));
+        let text = tree.render_with(&Guides::UNICODE.with_style(BLUE));
+
+        assert_eq!(text.lines()[0], RichString::from("root", BOLD));
+        assert_eq!(
+            text.lines()[1],
+            RichString::from("└── ", BLUE) + RichString::from("leaf", RED)
+        );
+    }
+}
@@ -0,0 +1,357 @@
+use crate::{RichStr, RichString};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Horizontal alignment of text
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub enum Alignment {
+    /// Aligned to the left
+    #[default]
+    LEFT,
+    /// Centered, with the extra space on the right
+    CENTER,
+    /// Aligned to the right
+    RIGHT,
+}
+
+impl RichString {
+    /// Returns the number of columns the string takes in a terminal
+    ///
+    /// Wide characters like most CJK ideographs take two columns, and
+    /// combining characters take none.
+    pub fn width(&self) -> usize {
+        self.raw().width()
+    }
+
+    /// Returns the beginning of the string that fits in a number of columns
+    pub fn truncate_width(&self, width: usize) -> Self {
+        self.as_rich_str().truncate_width(width).to_owned()
+    }
+
+    /// Cuts the string to fit in a number of columns, ending it with `…`
+    /// if anything was removed
+    ///
+    /// The string is emptied if the width is zero.
+    pub(crate) fn ellipsize(&self, width: usize) -> Self {
+        let mut ellipsized = Self::new();
+        ellipsized.push_ellipsized(self.as_rich_str(), width);
+        ellipsized
+    }
+
+    /// Appends a slice cut to fit in a number of columns, like
+    /// [Self::ellipsize()]
+    pub(crate) fn push_ellipsized(&mut self, line: RichStr<'_>, width: usize) {
+        if line.width() <= width {
+            self.push_slice(line);
+            return;
+        } else if width == 0 {
+            return;
+        }
+
+        let truncated = line.truncate_width(width.saturating_sub(1));
+        self.push_slice(truncated);
+        self.push(&Self::from("…", line.style_at(truncated.len())));
+    }
+
+    /// Pads the string with spaces to fill a number of columns
+    ///
+    /// The string is returned unchanged if it is already wider.
+    pub fn align(&self, width: usize, alignment: Alignment) -> Self {
+        let mut aligned = Self::new();
+        aligned.push_aligned(self.as_rich_str(), width, alignment);
+        aligned
+    }
+
+    /// Appends a slice padded with spaces, like [Self::align()]
+    pub(crate) fn push_aligned(&mut self, line: RichStr<'_>, width: usize, alignment: Alignment) {
+        self.push_padded(width, line.width(), alignment, |string| {
+            string.push_slice(line);
+        });
+    }
+
+    /// Appends content that takes `used` columns, with spaces around it to
+    /// fill `width` columns
+    pub(crate) fn push_padded<F: FnOnce(&mut Self)>(
+        &mut self,
+        width: usize,
+        used: usize,
+        alignment: Alignment,
+        push: F,
+    ) {
+        let extra = width.saturating_sub(used);
+        let left = match alignment {
+            Alignment::LEFT => 0,
+            Alignment::CENTER => extra / 2,
+            Alignment::RIGHT => extra,
+        };
+
+        self.push_plain(&" ".repeat(left));
+        push(self);
+        self.push_plain(&" ".repeat(extra - left));
+    }
+
+    /// Breaks the string into lines that fit in a number of columns
+    ///
+    /// Lines are broken on whitespace, and words that are too long are
+    /// broken anywhere. The whitespace where a line is broken is removed,
+    /// but line breaks already in the string are kept.
+    ///
+    /// Indentation is kept, but cut to leave room for some text, and lines
+    /// made only of whitespace that is too wide become empty. A character
+    /// wider than the width is put alone on its line. Nothing fits in zero
+    /// columns, so no lines are returned for a width of zero.
+    pub fn wrap(&self, width: usize) -> Vec<Self> {
+        self.as_rich_str()
+            .wrap(width)
+            .iter()
+            .map(RichStr::to_owned)
+            .collect()
+    }
+}
+
+impl<'a> RichStr<'a> {
+    /// Returns the number of columns the slice takes in a terminal
+    ///
+    /// See [RichString::width()].
+    pub fn width(&self) -> usize {
+        self.raw().width()
+    }
+
+    /// Returns the beginning of the slice that fits in a number of columns
+    pub fn truncate_width(&self, width: usize) -> Self {
+        let mut total = 0;
+        let mut end = 0;
+
+        for c in self.raw().chars() {
+            total += c.width().unwrap_or(0);
+            if total > width {
+                break;
+            }
+            end += 1;
+        }
+
+        self.slice(0, end)
+    }
+
+    /// Returns the number of columns the slice takes once ellipsized, see
+    /// [RichString::push_ellipsized()]
+    pub(crate) fn ellipsized_width(&self, width: usize) -> usize {
+        if self.width() <= width {
+            self.width()
+        } else if width == 0 {
+            0
+        } else {
+            self.truncate_width(width.saturating_sub(1)).width() + 1
+        }
+    }
+
+    /// Breaks the slice into lines that fit in a number of columns, without
+    /// copying them
+    ///
+    /// See [RichString::wrap()].
+    pub fn wrap(&self, width: usize) -> Vec<Self> {
+        if width == 0 {
+            return Vec::new();
+        }
+
+        let mut lines = Vec::new();
+        let mut offset = 0;
+
+        for source in self.raw().split('\n') {
+            let length = source.chars().count();
+            wrap_line(source, width, |start, end| {
+                lines.push(self.slice(offset + start, offset + end))
+            });
+            offset += length + 1;
+        }
+
+        lines
+    }
+}
+
+/// Wraps a single line, calling `emit` with the character range of each line
+fn wrap_line<F: FnMut(usize, usize)>(line: &str, width: usize, mut emit: F) {
+    let mut start = 0;
+    let mut end = 0;
+    let mut used = 0;
+    let mut has_content = false;
+    // the line only has indentation, which stays with the first word
+    let mut indented = false;
+    // whitespace between the end of the line and the next word
+    let mut space = 0;
+
+    for (index, word, is_space) in words(line) {
+        let length = word.chars().count();
+        let word_width = word.width();
+
+        if is_space {
+            if index == 0 {
+                // indentation is kept, but cut to leave at least one column
+                end = length;
+                used = word_width;
+                has_content = true;
+                indented = true;
+                for c in word.chars() {
+                    if used < width {
+                        break;
+                    }
+                    used -= c.width().unwrap_or(0);
+                    start += 1;
+                }
+            } else {
+                space += word_width;
+            }
+            continue;
+        }
+
+        if has_content && !indented && used + space + word_width > width {
+            emit(start, end);
+            has_content = false;
+        }
+        indented = false;
+        if has_content {
+            used += space;
+        } else {
+            start = index;
+            used = 0;
+        }
+        space = 0;
+
+        // words that are too long are broken anywhere
+        for (position, c) in (index..).zip(word.chars()) {
+            let char_width = c.width().unwrap_or(0);
+            if used + char_width > width && position > start {
+                emit(start, position);
+                start = position;
+                used = 0;
+            }
+            used += char_width;
+        }
+        end = index + length;
+        has_content = true;
+    }
+
+    if indented {
+        // the line is only whitespace
+        start = if line.width() > width { end } else { 0 };
+    }
+    emit(start, end);
+}
+
+/// Splits a line into words and whitespace, with their character index
+fn words(line: &str) -> impl Iterator<Item = (usize, &str, bool)> {
+    let mut rest = line;
+    let mut index = 0;
+
+    std::iter::from_fn(move || {
+        let first = rest.chars().next()?;
+        let is_space = first.is_whitespace();
+        let split = rest
+            .find(|c: char| c.is_whitespace() != is_space)
+            .unwrap_or(rest.len());
+
+        let (word, remaining) = rest.split_at(split);
+        let item = (index, word, is_space);
+        rest = remaining;
+        index += word.chars().count();
+        Some(item)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shortcuts::*;
+    use crate::Style;
+
+    fn raw(lines: Vec<RichString>) -> Vec<String> {
+        lines.iter().map(|line| String::from(line.raw())).collect()
+    }
+
+    #[test]
+    fn test_width() {
+        assert_eq!(RichString::new().width(), 0);
+        assert_eq!(RichString::from("abc", RED).width(), 3);
+        assert_eq!(RichString::from("日本", RED).width(), 4);
+        assert_eq!(RichString::from("e\u{301}", RED).width(), 1);
+    }
+
+    #[test]
+    fn test_truncate_width() {
+        let string = RichString::from("ab", RED) + "日本";
+        assert_eq!(string.truncate_width(10), string);
+        assert_eq!(string.truncate_width(3), RichString::from("ab", RED));
+        assert_eq!(string.truncate_width(4), RichString::from("ab", RED) + "日");
+        assert_eq!(string.truncate_width(0), RichString::new());
+    }
+
+    #[test]
+    fn test_align() {
+        let string = RichString::from("ab", RED);
+        assert_eq!(string.align(5, Alignment::LEFT), string.clone() + "   ");
+        assert_eq!(string.align(5, Alignment::RIGHT), "   " + string.clone());
+        assert_eq!(
+            string.align(5, Alignment::CENTER),
+            " " + string.clone() + "  "
+        );
+        assert_eq!(string.align(1, Alignment::CENTER), string);
+    }
+
+    #[test]
+    fn test_wrap() {
+        let string = RichString::from("the quick", RED) + " brown fox";
+        assert_eq!(raw(string.wrap(10)), vec!["the quick", "brown fox"]);
+        assert_eq!(string.wrap(10)[0], RichString::from("the quick", RED));
+        assert_eq!(
+            raw(string.wrap(4)),
+            vec!["the", "quic", "k", "brow", "n", "fox"]
+        );
+        assert_eq!(raw(string.wrap(100)), vec!["the quick brown fox"]);
+
+        let string = RichString::from("  indented  words\n\nend  ", Style::plain());
+        assert_eq!(raw(string.wrap(10)), vec!["  indented", "words", "", "end"]);
+
+        assert_eq!(raw(RichString::new().wrap(5)), vec![""]);
+        assert_eq!(raw(string.wrap(0)), Vec::<String>::new());
+        assert_eq!(
+            raw(RichString::from("日本語", RED).wrap(3)),
+            vec!["日", "本", "語"]
+        );
+        assert_eq!(
+            raw(RichString::from("日本語", RED).wrap(1)),
+            vec!["日", "本", "語"]
+        );
+    }
+
+    #[test]
+    fn test_wrap_indentation() {
+        let string = RichString::from("        indented words here", RED);
+        assert_eq!(
+            raw(string.wrap(4)),
+            vec!["   i", "nden", "ted", "word", "s", "here"]
+        );
+        assert_eq!(
+            raw(string.wrap(12)),
+            vec!["        inde", "nted words", "here"]
+        );
+        assert_eq!(
+            raw(string.wrap(1)),
+            vec![
+                "i", "n", "d", "e", "n", "t", "e", "d", "w", "o", "r", "d", "s", "h", "e", "r", "e"
+            ]
+        );
+
+        let blank = RichString::from("          \n   ", RED);
+        assert_eq!(raw(blank.wrap(3)), vec!["", "   "]);
+        assert!(blank.wrap(3).iter().all(|line| line.width() <= 3));
+    }
+
+    #[test]
+    fn test_ellipsize() {
+        let string = RichString::from("abc", RED) + "def";
+        assert_eq!(string.ellipsize(10), string);
+        assert_eq!(string.ellipsize(4), RichString::from("abc", RED) + "…");
+        assert_eq!(string.ellipsize(1), RichString::from("…", RED));
+        assert_eq!(string.ellipsize(0), RichString::new());
+        assert_eq!(string.as_rich_str().ellipsized_width(0), 0);
+    }
+}
@@ -0,0 +1,121 @@
+use crate::RichString;
+use std::io::{self, Write};
+
+/// Writes rich strings to a terminal or a file
+///
+/// A line can be redrawn in place, which is how progress bars and spinners
+/// are animated.
+#[derive(Debug)]
+pub struct StyledWriter<W: Write> {
+    inner: W,
+    styled: bool,
+    /// Whether a line is being redrawn
+    redrawing: bool,
+}
+
+impl<W: Write> StyledWriter<W> {
+    /// Creates a writer that outputs styles as escape sequences
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            styled: true,
+            redrawing: false,
+        }
+    }
+
+    /// Creates a writer that outputs text without styles
+    pub fn plain(inner: W) -> Self {
+        Self {
+            styled: false,
+            ..Self::new(inner)
+        }
+    }
+
+    /// Writes a string
+    ///
+    /// If a line was being redrawn, it is ended first.
+    pub fn write(&mut self, string: &RichString) -> io::Result<()> {
+        self.finish()?;
+        self.write_styled(string)
+    }
+
+    /// Writes a string followed by a newline
+    pub fn writeln(&mut self, string: &RichString) -> io::Result<()> {
+        self.write(string)?;
+        self.inner.write_all(b"\n")
+    }
+
+    /// Replaces the current line with a string
+    ///
+    /// The cursor goes back to the beginning of the line with a carriage
+    /// return, and the line is erased with an escape sequence before the
+    /// string is drawn.
+    pub fn redraw(&mut self, string: &RichString) -> io::Result<()> {
+        self.inner.write_all(b"\r\x1b[K")?;
+        self.write_styled(string)?;
+        self.redrawing = true;
+        self.inner.flush()
+    }
+
+    /// Ends the line being redrawn, if any, so that it stays visible
+    pub fn finish(&mut self) -> io::Result<()> {
+        if std::mem::take(&mut self.redrawing) {
+            self.inner.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
+    /// Flushes the output
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
+    /// Returns the underlying writer
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    fn write_styled(&mut self, string: &RichString) -> io::Result<()> {
+        if self.styled {
+            self.inner.write_all(string.to_ansi().as_bytes())
+        } else {
+            self.inner.write_all(string.raw().as_bytes())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shortcuts::*;
+    use crate::Style;
+
+    #[test]
+    fn test_write() {
+        let mut writer = StyledWriter::new(Vec::new());
+        writer.write(&RichString::from("a", RED)).unwrap();
+        writer
+            .writeln(&RichString::from("b", Style::plain()))
+            .unwrap();
+        assert_eq!(writer.into_inner(), b"\x1b[31ma\x1b[0mb\n");
+
+        let mut writer = StyledWriter::plain(Vec::new());
+        writer.writeln(&RichString::from("a", RED)).unwrap();
+        assert_eq!(writer.into_inner(), b"a\n");
+    }
+
+    #[test]
+    fn test_redraw() {
+        let mut writer = StyledWriter::plain(Vec::new());
+        writer.redraw(&RichString::from("10%", RED)).unwrap();
+        writer.redraw(&RichString::from("100%", RED)).unwrap();
+        writer.redraw(&RichString::from("ok", RED)).unwrap();
+        writer.writeln(&RichString::from("done", RED)).unwrap();
+        writer.finish().unwrap();
+
+        assert_eq!(
+            String::from_utf8(writer.into_inner()).unwrap(),
+            "\r\x1b[K10%\r\x1b[K100%\r\x1b[Kok\ndone\n"
+        );
+    }
+}